@@ -0,0 +1,68 @@
+// Fetches the answer to today's NYT Wordle from its public data endpoint
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ENDPOINT: &str = "https://www.nytimes.com/svc/wordle/v2";
+
+pub fn fetch_daily_answer() -> Result<String, String> {
+    fetch_answer_for(&today())
+}
+
+// `date` is formatted as YYYY-MM-DD
+pub fn fetch_answer_for(date: &str) -> Result<String, String> {
+    answer_for(date, get)
+}
+
+// Like fetch_answer_for, with `fetch` turning the URL into the response body, so the request
+// can be replaced e.g. by a canned response
+pub fn answer_for(
+    date: &str,
+    fetch: impl FnOnce(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let url = format!("{}/{}.json", ENDPOINT, date);
+    let body = fetch(&url)?;
+
+    parse_solution(&body).ok_or_else(|| format!("No solution found in the response from {}", url))
+}
+
+fn get(url: &str) -> Result<String, String> {
+    ureq::get(url)
+        .call()
+        .map_err(|err| format!("Failed to fetch the daily answer: {}", err))?
+        .into_string()
+        .map_err(|err| format!("Failed to read the response from {}: {}", url, err))
+}
+
+fn parse_solution(body: &str) -> Option<String> {
+    let start = body.find("\"solution\"")? + "\"solution\"".len();
+    let rest = body[start..].trim_start().strip_prefix(':')?.trim_start();
+    let rest = rest.strip_prefix('"')?;
+    let solution = &rest[..rest.find('"')?];
+
+    if solution.len() == crate::WORD_LENGTH && solution.bytes().all(|c| c.is_ascii_alphabetic()) {
+        Some(solution.to_lowercase())
+    } else {
+        None
+    }
+}
+
+// Today's date in UTC, which can be a day off from the puzzle shown in some time zones
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        / 86400;
+
+    // Converts days since the epoch to a civil date (from Howard Hinnant's date algorithms)
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,6 @@
+// Word lists shared by the library and binary tests
+
+// Words that differ in only one letter, guessable only by trying them one by one
+pub const IGHT: [&str; 9] = [
+    "bight", "eight", "fight", "light", "might", "night", "right", "sight", "tight",
+];
@@ -0,0 +1,4155 @@
+use rayon::prelude::*;
+use std::{
+    array,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+#[cfg(feature = "network")]
+pub mod daily;
+#[cfg(test)]
+mod fixtures;
+mod opening_book;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod table_cache;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "serde_impls::ConstraintsRepr",
+        try_from = "serde_impls::ConstraintsRepr"
+    )
+)]
+pub struct Constraints<const N: usize = WORD_LENGTH> {
+    known_letters: [Option<u8>; N],
+    included_letters: [Vec<u8>; N],
+    excluded_letters: Vec<u8>,
+    min_counts: BTreeMap<u8, usize>,
+    max_counts: BTreeMap<u8, usize>,
+}
+
+impl Constraints {
+    pub fn new() -> Constraints {
+        Self::default()
+    }
+}
+
+impl<const N: usize> Constraints<N> {
+    // Letters whose number of occurrences in the answer is fully determined
+    pub fn exact_counts(&self) -> Vec<(u8, usize)> {
+        self.min_counts
+            .iter()
+            .filter(|&(c, &min)| min > 0 && self.max_counts.get(c) == Some(&min))
+            .map(|(&c, &min)| (c, min))
+            .collect()
+    }
+
+    // Whether a green, a yellow or a 'require' showed the answer has the letter
+    pub fn is_present(&self, c: u8) -> bool {
+        self.min_counts.get(&c).is_some_and(|&min| min > 0)
+    }
+
+    // A letter known to be present is left alone, since excluding it would rule out every word
+    pub fn exclude_letter(&mut self, c: u8) {
+        if self.is_present(c) {
+            return;
+        }
+        if !self.excluded_letters.contains(&c) {
+            self.excluded_letters.push(c);
+        }
+        self.max_counts.insert(c, 0);
+    }
+
+    // Rules the letter out at one (zero-based) position without saying anything about the rest
+    pub fn exclude_at(&mut self, position: usize, c: u8) {
+        if !self.included_letters[position].contains(&c) {
+            self.included_letters[position].push(c);
+        }
+    }
+
+    // Requires the letter to appear somewhere in the answer, without saying where
+    pub fn require_letter(&mut self, c: u8) {
+        let min = self.min_counts.entry(c).or_insert(0);
+        *min = (*min).max(1);
+        self.reconcile();
+    }
+
+    // A letter ruled out earlier, e.g. by a mistyped result or a wrong 'exclude', can later be
+    // shown to be present. The letter stops being excluded and a cap below its minimum is
+    // dropped, so the constraints don't contradict themselves and rule out every word
+    fn reconcile(&mut self) {
+        let min_counts = &self.min_counts;
+        let present = |c: &u8| min_counts.get(c).is_some_and(|&min| min > 0);
+
+        self.excluded_letters.retain(|c| !present(c));
+        self.max_counts
+            .retain(|c, max| min_counts.get(c).is_none_or(|min| *max >= *min));
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        let word = word.as_bytes();
+
+        if self.excluded_letters.iter().any(|&c| word.contains(&c)) {
+            return false;
+        }
+
+        for (i, c) in word.iter().enumerate() {
+            if let Some(letter) = self.known_letters[i] {
+                if c != &letter {
+                    return false;
+                }
+            } else if self.included_letters[i].contains(c) {
+                return false;
+            }
+        }
+
+        let count = |c: u8| word.iter().filter(|&&letter| letter == c).count();
+
+        if self.min_counts.iter().any(|(&c, &min)| count(c) < min) {
+            return false;
+        }
+
+        if self.max_counts.iter().any(|(&c, &max)| count(c) > max) {
+            return false;
+        }
+
+        true
+    }
+
+    // The first check in `matches` that rejects the word, described for the user
+    pub fn violation(&self, word: &str) -> Option<String> {
+        let bytes = word.as_bytes();
+
+        if let Some(&c) = self.excluded_letters.iter().find(|&c| bytes.contains(c)) {
+            return Some(format!("has excluded letter '{}'", c as char));
+        }
+
+        for (i, &c) in bytes.iter().enumerate() {
+            if let Some(letter) = self.known_letters[i] {
+                if c != letter {
+                    return Some(format!(
+                        "wrong letter at position {}: '{}' instead of '{}'",
+                        i + 1,
+                        c as char,
+                        letter as char
+                    ));
+                }
+            } else if self.included_letters[i].contains(&c) {
+                return Some(format!("'{}' can't be at position {}", c as char, i + 1));
+            }
+        }
+
+        let count = |c: u8| bytes.iter().filter(|&&letter| letter == c).count();
+
+        if let Some((&c, &min)) = self.min_counts.iter().find(|(&c, &min)| count(c) < min) {
+            return Some(if min == 1 {
+                format!("missing required letter '{}'", c as char)
+            } else {
+                format!("needs at least {} '{}'s", min, c as char)
+            });
+        }
+
+        if let Some((&c, &max)) = self.max_counts.iter().find(|(&c, &max)| count(c) > max) {
+            return Some(format!("has more than {} '{}'s", max, c as char));
+        }
+
+        None
+    }
+
+    pub fn update_from_guess(&mut self, guess: &str, output: [WordleAnswerColor; N]) {
+        self.update(guess, output, true);
+    }
+
+    // For clones where a yellow only means the letter is somewhere in the answer, possibly
+    // even at the yellow's own position
+    pub fn update_from_lenient_guess(&mut self, guess: &str, output: [WordleAnswerColor; N]) {
+        self.update(guess, output, false);
+    }
+
+    fn update(&mut self, guess: &str, output: [WordleAnswerColor; N], strict_yellow: bool) {
+        let mut present: BTreeMap<u8, usize> = BTreeMap::new();
+        for (&c, color) in guess.as_bytes().iter().zip(output) {
+            if color != WordleAnswerColor::Gray {
+                *present.entry(c).or_insert(0) += 1;
+            }
+        }
+
+        for (i, &c) in guess.as_bytes().iter().enumerate() {
+            match output[i] {
+                WordleAnswerColor::Green => {
+                    self.known_letters[i] = Some(c);
+                }
+                WordleAnswerColor::Yellow if !strict_yellow => {}
+                // A gray copy of a letter that is colored elsewhere in the guess only tells us
+                // the letter isn't at this position, the same as a yellow does
+                WordleAnswerColor::Yellow | WordleAnswerColor::Gray if present.contains_key(&c) => {
+                    if !self.included_letters[i].contains(&c) {
+                        self.included_letters[i].push(c);
+                    }
+                }
+                WordleAnswerColor::Yellow | WordleAnswerColor::Gray => {
+                    if !self.excluded_letters.contains(&c) {
+                        self.excluded_letters.push(c);
+                    }
+                }
+            }
+        }
+
+        for (&c, &count) in &present {
+            let min = self.min_counts.entry(c).or_insert(0);
+            *min = (*min).max(count);
+        }
+
+        // A gray copy of a letter means the answer has no more of it than were colored
+        for (&c, color) in guess.as_bytes().iter().zip(output) {
+            if color == WordleAnswerColor::Gray {
+                let count = present.get(&c).copied().unwrap_or(0);
+                let max = self.max_counts.entry(c).or_insert(count);
+                *max = (*max).min(count);
+            }
+        }
+
+        self.reconcile();
+    }
+}
+
+impl<const N: usize> Default for Constraints<N> {
+    fn default() -> Self {
+        Constraints {
+            known_letters: [None; N],
+            included_letters: array::from_fn(|_| Vec::new()),
+            excluded_letters: Vec::new(),
+            min_counts: BTreeMap::new(),
+            max_counts: BTreeMap::new(),
+        }
+    }
+}
+
+impl<const N: usize> fmt::Display for Constraints<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let known: String = self
+            .known_letters
+            .iter()
+            .map(|c| c.map_or('_', char::from))
+            .collect();
+        writeln!(f, "Known:     {}", known)?;
+
+        let misplaced: Vec<String> = self
+            .included_letters
+            .iter()
+            .enumerate()
+            .flat_map(|(i, set)| set.iter().map(move |&c| format!("{}@{}", c as char, i + 1)))
+            .collect();
+        writeln!(f, "Misplaced: {}", misplaced.join(" "))?;
+
+        let excluded: String = self.excluded_letters.iter().map(|&c| c as char).collect();
+        writeln!(f, "Excluded:  {}", excluded)?;
+
+        let exact: Vec<String> = self
+            .exact_counts()
+            .iter()
+            .map(|&(c, count)| format!("{}={}", c as char, count))
+            .collect();
+        write!(f, "Exactly:   {}", exact.join(" "))
+    }
+}
+
+// Words and constraints keep letters as ASCII bytes while the rest of the API takes chars.
+// Only 'a'..='z' converts either way, so a stray uppercase or non-ASCII letter shows up as None
+// instead of being truncated by a cast into some other letter's slot
+pub fn byte_to_char(c: u8) -> Option<char> {
+    c.is_ascii_lowercase().then_some(c as char)
+}
+
+pub fn char_to_byte(c: char) -> Option<u8> {
+    c.is_ascii_lowercase().then_some(c as u8)
+}
+
+// Position of a letter in the alphabet, or None for anything outside 'a'..='z'
+pub fn letter_index(c: char) -> Option<usize> {
+    char_to_byte(c).map(|c| (c - b'a') as usize)
+}
+
+// How often a guess has been scored against an answer in this process, by simulate_guess or
+// the pattern codes the entropy works from, to measure how much work the search does. Only
+// counted with the 'profiling' feature so the hot path stays untouched without it
+#[cfg(feature = "profiling")]
+static SIMULATE_GUESS_CALLS: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(feature = "profiling")]
+pub fn simulate_guess_calls() -> u64 {
+    SIMULATE_GUESS_CALLS.load(Ordering::Relaxed)
+}
+
+// Greens are assigned first, then each remaining letter of the answer can turn at most one
+// other copy of it yellow, left to right, like Wordle does. Guessing "aaaaa" against "araba"
+// gives gxgxg since every 'a' in the answer is used up by a green, and "eerie" against
+// "there" gives yxyxg since only one of the two unplaced 'e's can be matched.
+pub fn simulate_guess<const N: usize>(correct: &str, guess: &str) -> [WordleAnswerColor; N] {
+    let mut output = [WordleAnswerColor::Gray; N];
+    score_guess(correct, guess, |i, color| output[i] = color);
+    output
+}
+
+// The pattern read as a base 3 number, below 3^N. Words of any length can be told apart with
+// it, so the measures that only count patterns don't depend on N
+fn pattern_code(correct: &str, guess: &str) -> usize {
+    let mut code = 0;
+    score_guess(correct, guess, |_, color| code = code * 3 + color as usize);
+    code
+}
+
+// How many patterns words of `length` letters can get
+pub fn pattern_count(length: usize) -> usize {
+    3usize.pow(length as u32)
+}
+
+// Hands every position's color to `color` in order, shared by simulate_guess and pattern_code
+fn score_guess(correct: &str, guess: &str, mut color: impl FnMut(usize, WordleAnswerColor)) {
+    debug_assert!(correct.is_ascii() && guess.is_ascii());
+    #[cfg(feature = "profiling")]
+    SIMULATE_GUESS_CALLS.fetch_add(1, Ordering::Relaxed);
+    let (correct, guess) = (correct.as_bytes(), guess.as_bytes());
+
+    let mut unmatched = [0u8; 26];
+    for (&c, &answer) in guess.iter().zip(correct) {
+        if c != answer {
+            if let Some(index) = letter_index(answer as char) {
+                unmatched[index] += 1;
+            }
+        }
+    }
+
+    for (i, (&c, &answer)) in guess.iter().zip(correct).enumerate() {
+        let output = if c == answer {
+            WordleAnswerColor::Green
+        } else {
+            match letter_index(c as char) {
+                Some(index) if unmatched[index] > 0 => {
+                    unmatched[index] -= 1;
+                    WordleAnswerColor::Yellow
+                }
+                _ => WordleAnswerColor::Gray,
+            }
+        };
+        color(i, output);
+    }
+}
+
+/// The colors Wordle shows for `guess` when the answer is `answer`, for callers outside the
+/// solver. Unlike simulate_guess it checks its input, and fails unless both words are
+/// N lowercase ASCII letters
+///
+/// ```
+/// use ideal_wordle_player::{feedback, WordleAnswerColor::*};
+///
+/// // t is in place, a and e are in the word but elsewhere, s and l aren't in it
+/// assert_eq!(feedback("react", "salet"), Ok([Gray, Yellow, Gray, Yellow, Green]));
+///
+/// // Only one of the two e's in "eerie" can match the unplaced e of "there"
+/// assert_eq!(feedback("there", "eerie"), Ok([Yellow, Gray, Yellow, Gray, Green]));
+///
+/// // N is taken from the pattern it is compared with, so other variants work the same way
+/// assert_eq!(feedback("lane", "line"), Ok([Green, Gray, Green, Green]));
+///
+/// assert!(feedback::<5>("crane", "cranes").is_err());
+/// assert!(feedback::<5>("Crane", "crate").is_err());
+/// ```
+pub fn feedback<const N: usize>(
+    answer: &str,
+    guess: &str,
+) -> Result<[WordleAnswerColor; N], String> {
+    for word in [answer, guess] {
+        if word.len() != N || !word.bytes().all(|c| c.is_ascii_lowercase()) {
+            return Err(format!("'{}' is not a {}-letter lowercase word", word, N));
+        }
+    }
+
+    Ok(simulate_guess(answer, guess))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HardModeViolation {
+    // A green letter wasn't kept in place. Positions are counted from 1
+    MovedGreen { position: usize, letter: char },
+    // A revealed letter was used fewer times than it was revealed
+    MissingLetter { letter: char, count: usize },
+}
+
+impl fmt::Display for HardModeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HardModeViolation::MovedGreen { position, letter } => {
+                write!(f, "letter {} must be '{}'", position, letter)
+            }
+            HardModeViolation::MissingLetter { letter, count: 1 } => {
+                write!(f, "it must contain '{}'", letter)
+            }
+            HardModeViolation::MissingLetter { letter, count } => {
+                write!(f, "it must contain {} '{}'s", count, letter)
+            }
+        }
+    }
+}
+
+// Wordle's own hard mode rule: greens have to stay in place and every revealed letter has to
+// be reused. The solver's hard mode is stricter and also avoids letters known to be gray
+pub fn hard_mode_legal(guess: &str, previous: &str, pattern: &[WordleAnswerColor]) -> bool {
+    hard_mode_violation(guess, previous, pattern).is_none()
+}
+
+// The first way the guess breaks hard mode after `previous` got `pattern`, greens first
+pub fn hard_mode_violation(
+    guess: &str,
+    previous: &str,
+    pattern: &[WordleAnswerColor],
+) -> Option<HardModeViolation> {
+    let (guess, previous) = (guess.as_bytes(), previous.as_bytes());
+    let mut required = [0usize; 26];
+
+    for (i, color) in pattern.iter().enumerate() {
+        if *color == WordleAnswerColor::Green && guess[i] != previous[i] {
+            return Some(HardModeViolation::MovedGreen {
+                position: i + 1,
+                letter: previous[i] as char,
+            });
+        }
+        if let (true, Some(index)) = (
+            *color != WordleAnswerColor::Gray,
+            letter_index(previous[i] as char),
+        ) {
+            required[index] += 1;
+        }
+    }
+
+    ('a'..='z').zip(required).find_map(|(letter, count)| {
+        let byte = char_to_byte(letter)?;
+        (guess.iter().filter(|&&c| c == byte).count() < count)
+            .then_some(HardModeViolation::MissingLetter { letter, count })
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameInconsistency {
+    // Turns are counted from 1, `length` is how many letters the game's words have
+    InvalidGuess {
+        turn: usize,
+        guess: String,
+        length: usize,
+    },
+    // No word at all could give this pattern for the guess, e.g. a gray copy of a letter
+    // before a yellow one
+    ImpossiblePattern {
+        turn: usize,
+    },
+    // Every answer that fits the earlier turns is ruled out by this one
+    NoAnswer {
+        turn: usize,
+    },
+    // The game went on after it was solved
+    AfterSolved {
+        turn: usize,
+    },
+}
+
+impl fmt::Display for GameInconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameInconsistency::InvalidGuess {
+                turn,
+                guess,
+                length,
+            } => write!(
+                f,
+                "turn {}: '{}' is not a {} letter word",
+                turn, guess, length
+            ),
+            GameInconsistency::ImpossiblePattern { turn } => {
+                write!(f, "turn {}: no word can give this result", turn)
+            }
+            GameInconsistency::NoAnswer { turn } => {
+                write!(f, "turn {}: no answer fits every result so far", turn)
+            }
+            GameInconsistency::AfterSolved { turn } => {
+                write!(f, "turn {}: the game was already solved", turn)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GameInconsistency {}
+
+// Whether any string of letters, word or not, gets this pattern for the guess. Wordle colors
+// the copies of a letter left to right, so the yellows have to come before the grays, and each
+// yellow needs its own free spot that isn't one of that letter's own
+pub fn pattern_possible(guess: &str, pattern: &[WordleAnswerColor]) -> bool {
+    let guess = guess.as_bytes();
+    let free: Vec<usize> = (0..pattern.len())
+        .filter(|&i| pattern[i] != WordleAnswerColor::Green)
+        .collect();
+
+    let mut yellows = Vec::new();
+    for &i in &free {
+        let c = guess[i];
+        let gray_before = free
+            .iter()
+            .any(|&j| j < i && guess[j] == c && pattern[j] == WordleAnswerColor::Gray);
+        match pattern[i] {
+            WordleAnswerColor::Yellow if gray_before => return false,
+            WordleAnswerColor::Yellow => yellows.push(c),
+            _ => {}
+        }
+    }
+
+    fn place(yellows: &[u8], free: &[usize], guess: &[u8], used: &mut Vec<usize>) -> bool {
+        let Some((&c, rest)) = yellows.split_first() else {
+            return true;
+        };
+        for &spot in free {
+            if guess[spot] != c && !used.contains(&spot) {
+                used.push(spot);
+                if place(rest, free, guess, used) {
+                    return true;
+                }
+                used.pop();
+            }
+        }
+        false
+    }
+
+    place(&yellows, &free, guess, &mut Vec::new())
+}
+
+// Checks that a played game could have happened with one of `answers`, narrowing them down
+// turn by turn
+pub fn validate_game<const N: usize>(
+    turns: &[(String, [WordleAnswerColor; N])],
+    answers: &[&str],
+) -> Result<(), GameInconsistency> {
+    let mut remaining = answers.to_vec();
+
+    for (i, (guess, pattern)) in turns.iter().enumerate() {
+        let turn = i + 1;
+        if i > 0 && turns[i - 1].1 == [WordleAnswerColor::Green; N] {
+            return Err(GameInconsistency::AfterSolved { turn });
+        }
+        if guess.len() != N || !guess.bytes().all(|c| c.is_ascii_lowercase()) {
+            return Err(GameInconsistency::InvalidGuess {
+                turn,
+                guess: guess.clone(),
+                length: N,
+            });
+        }
+        if !pattern_possible(guess, pattern) {
+            return Err(GameInconsistency::ImpossiblePattern { turn });
+        }
+
+        remaining.retain(|answer| simulate_guess(answer, guess) == *pattern);
+        if remaining.is_empty() {
+            return Err(GameInconsistency::NoAnswer { turn });
+        }
+    }
+
+    Ok(())
+}
+
+// Mastermind-style feedback: (letters in the right spot, right letters in the wrong spot)
+pub fn count_feedback(correct: &str, guess: &str) -> (u8, u8) {
+    let mut counts = (0, 0);
+    score_guess(correct, guess, |_, color| match color {
+        WordleAnswerColor::Green => counts.0 += 1,
+        WordleAnswerColor::Yellow => counts.1 += 1,
+        WordleAnswerColor::Gray => {}
+    });
+    counts
+}
+
+pub fn shannon_entropy<K>(distribution: &HashMap<K, usize>, total: usize) -> f64 {
+    // HashMap iteration order differs between instances, so sum in a fixed order to keep
+    // the result bit-for-bit reproducible
+    let mut counts: Vec<usize> = distribution.values().copied().collect();
+    counts.sort_unstable();
+
+    counts
+        .into_iter()
+        .map(|count| {
+            let p = count as f64 / total as f64;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+pub fn pattern_distribution<const N: usize>(
+    guess: &str,
+    words: &[&str],
+) -> HashMap<[WordleAnswerColor; N], usize> {
+    let mut distribution = HashMap::new();
+
+    for &word in words {
+        let pattern = simulate_guess(word, guess);
+        *distribution.entry(pattern).or_insert(0) += 1;
+    }
+
+    distribution
+}
+
+// pattern_distribution keyed by pattern_code, for measures that don't need the patterns
+fn code_distribution(guess: &str, words: &[&str]) -> HashMap<usize, usize> {
+    let mut distribution = HashMap::new();
+
+    for &word in words {
+        *distribution.entry(pattern_code(word, guess)).or_insert(0) += 1;
+    }
+
+    distribution
+}
+
+// Rough number of guesses still needed to find the answer among `count` candidates. Guessing
+// them in turn can't average better than (2k - 1) / k, and beyond a few candidates each guess
+// is worth about FOLLOW_UP_BITS bits
+fn follow_up_cost(count: usize) -> f64 {
+    if count <= 1 {
+        return count as f64;
+    }
+
+    let count = count as f64;
+    ((2. * count - 1.) / count).max(1. + count.log2() / FOLLOW_UP_BITS)
+}
+
+// Expected guesses until the answer is found when `guess` is played next, this one included
+fn expected_guesses(guess: &str, remaining: &[&str], probability: impl Fn(&str) -> f64) -> f64 {
+    let mut buckets: HashMap<usize, (usize, f64)> = HashMap::new();
+    for &word in remaining {
+        let bucket = buckets.entry(pattern_code(word, guess)).or_default();
+        bucket.0 += 1;
+        bucket.1 += probability(word);
+    }
+
+    // Codes sort like the patterns do, and all green is 0
+    let mut buckets: Vec<_> = buckets.into_iter().collect();
+    buckets.sort_unstable_by_key(|&(code, _)| code);
+    1. + buckets
+        .iter()
+        .filter(|&&(code, _)| code != 0)
+        .map(|&(_, (count, probability))| probability * follow_up_cost(count))
+        .sum::<f64>()
+}
+
+// Weighs going for the answer now against narrowing it down: the likeliest candidate wins
+// outright with its probability, the guess with the most entropy usually leaves less behind.
+// Whichever is expected to finish in fewer guesses is played, and on the last guess only a
+// candidate can win
+pub fn ev_guess(
+    remaining: &[&'static str],
+    probabilities: &HashMap<&str, f64>,
+    all_words: &[&'static str],
+    guesses_left: usize,
+) -> &'static str {
+    let uniform = 1. / remaining.len() as f64;
+    let probability = |word: &str| probabilities.get(word).copied().unwrap_or(uniform);
+
+    let likeliest = remaining
+        .iter()
+        .copied()
+        .max_by(|a, b| {
+            probability(a)
+                .total_cmp(&probability(b))
+                .then_with(|| b.cmp(a))
+        })
+        .expect("there is always a candidate");
+    if guesses_left <= 1 || remaining.len() == 1 {
+        return likeliest;
+    }
+
+    let informative = all_words
+        .par_iter()
+        .map(|&word| (word, guess_entropy(word, remaining)))
+        .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map_or(likeliest, |(word, _)| word);
+
+    if expected_guesses(informative, remaining, probability)
+        < expected_guesses(likeliest, remaining, probability)
+    {
+        informative
+    } else {
+        likeliest
+    }
+}
+
+pub fn guess_entropy(guess: &str, words: &[&str]) -> f64 {
+    shannon_entropy(&code_distribution(guess, words), words.len())
+}
+
+// Share of `guesses` that split the candidates no better than `guess` does, so 1 means nothing
+// gets more information out of this state
+pub fn entropy_percentile(guess: &str, guesses: &[&str], candidates: &[&str]) -> f64 {
+    if guesses.is_empty() {
+        return 1.;
+    }
+
+    let entropy = guess_entropy(guess, candidates);
+    let worse = guesses
+        .par_iter()
+        .filter(|&&other| guess_entropy(other, candidates) <= entropy + 1e-9)
+        .count();
+    worse as f64 / guesses.len() as f64
+}
+
+// How many different patterns the guess can get, a cheaper stand-in for its entropy. Patterns
+// are only marked off in a bitset instead of being counted in a map
+pub fn distinct_outcomes(guess: &str, candidates: &[&str]) -> usize {
+    let mut seen = vec![0u64; pattern_count(guess.len()).div_ceil(64)];
+    for &word in candidates {
+        let index = pattern_code(word, guess);
+        seen[index / 64] |= 1 << (index % 64);
+    }
+
+    seen.iter().map(|bits| bits.count_ones() as usize).sum()
+}
+
+// The pattern read as a base 3 number like pattern_code does
+fn pattern_index(pattern: &[WordleAnswerColor]) -> usize {
+    pattern
+        .iter()
+        .fold(0, |index, &color| index * 3 + color as usize)
+}
+
+// Fewest guesses that could narrow `candidates` words down to one when no guess gets more than
+// `outcomes` patterns, i.e. ceil(log_outcomes(candidates)). None if it can't be done at all
+pub fn guesses_lower_bound(candidates: usize, outcomes: usize) -> Option<usize> {
+    let mut guesses = 0;
+    let mut reachable: usize = 1;
+    while reachable < candidates {
+        if outcomes <= 1 {
+            return None;
+        }
+        reachable = reachable.saturating_mul(outcomes);
+        guesses += 1;
+    }
+    Some(guesses)
+}
+
+// Size of the largest pattern bucket, i.e. how many candidates remain in the worst case
+pub fn max_bucket_size(guess: &str, candidates: &[&str]) -> usize {
+    code_distribution(guess, candidates)
+        .into_values()
+        .max()
+        .unwrap_or(0)
+}
+
+// Every pattern the guess can produce against the candidates, sorted with greens first
+pub fn possible_patterns<const N: usize>(
+    guess: &str,
+    candidates: &[&str],
+) -> Vec<[WordleAnswerColor; N]> {
+    let mut patterns: Vec<[WordleAnswerColor; N]> = pattern_distribution(guess, candidates)
+        .into_keys()
+        .collect();
+    patterns.sort_unstable();
+    patterns
+}
+
+#[derive(Debug, Clone)]
+pub struct EntropyReport {
+    pub entropy: f64,
+    pub buckets: usize,
+    pub max_bucket_size: usize,
+}
+
+pub fn guess_entropy_report(guess: &str, candidates: &[&str]) -> EntropyReport {
+    let distribution = code_distribution(guess, candidates);
+
+    EntropyReport {
+        entropy: shannon_entropy(&distribution, candidates.len()),
+        buckets: distribution.len(),
+        max_bucket_size: distribution.values().copied().max().unwrap_or(0),
+    }
+}
+
+pub fn word_likelihood_score(word: &str, freq_data: &[HashMap<u8, f64>]) -> f64 {
+    word.as_bytes()
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| *freq_data[i].get(&c).unwrap_or(&0.))
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScoringWeights {
+    pub bayesian: f64,
+    pub valid_bias: f64,
+    pub likelihood: f64,
+    pub seen_bias: f64,
+    pub coverage: f64,
+    pub yellow_placement: f64,
+    // Subtracted per candidate in the guess's largest outcome bucket
+    pub worst_case: f64,
+    pub blend: Blend,
+    pub likelihood_norm: LikelihoodNorm,
+    pub metric: Metric,
+}
+
+// How the raw likelihood (a sum of per-position letter frequencies) is scaled before weighting
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LikelihoodNorm {
+    #[default]
+    Sum,
+    // Divided by the word length
+    Average,
+    // Standardized against the likelihoods of the remaining candidates, so the term keeps the
+    // same spread as the candidate set shrinks
+    ZScore,
+}
+
+// Spread of the candidates' likelihoods, which ZScore measures each guess against. It only
+// depends on the candidates, so it's worked out once per search rather than once per guess
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LikelihoodStats {
+    pub mean: f64,
+    pub deviation: f64,
+}
+
+impl LikelihoodNorm {
+    // Only ZScore needs the stats, the other normalizations get zeroes
+    pub fn stats(self, words: &[&str], freq_data: &[HashMap<u8, f64>]) -> LikelihoodStats {
+        if self != LikelihoodNorm::ZScore || words.is_empty() {
+            return LikelihoodStats::default();
+        }
+
+        let scores: Vec<f64> = words
+            .iter()
+            .map(|word| word_likelihood_score(word, freq_data))
+            .collect();
+        let n = scores.len() as f64;
+        let mean = scores.iter().sum::<f64>() / n;
+        let deviation = (scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+        LikelihoodStats { mean, deviation }
+    }
+
+    // `letters` is the length of the word the likelihood was summed over
+    pub fn apply(self, likelihood: f64, letters: usize, stats: &LikelihoodStats) -> f64 {
+        match self {
+            LikelihoodNorm::Sum => likelihood,
+            LikelihoodNorm::Average => likelihood / letters as f64,
+            LikelihoodNorm::ZScore if stats.deviation > 0. => {
+                (likelihood - stats.mean) / stats.deviation
+            }
+            LikelihoodNorm::ZScore => 0.,
+        }
+    }
+}
+
+// How a guess's information is measured
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Metric {
+    #[default]
+    Entropy,
+    // log2 of the number of distinct outcomes, which is what the entropy would be if every
+    // outcome were equally likely
+    Buckets,
+}
+
+// How a guess's entropy is combined with its probability of being the answer
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Blend {
+    // entropy + bayesian * weight
+    #[default]
+    Additive,
+    // entropy * (1 + bayesian * weight), so probability can't outweigh a guess that tells
+    // us nothing
+    Multiplicative,
+}
+
+impl Blend {
+    pub fn apply(self, entropy: f64, bayesian: f64, weight: f64) -> f64 {
+        match self {
+            Blend::Additive => entropy + bayesian * weight,
+            Blend::Multiplicative => entropy * (1. + bayesian * weight),
+        }
+    }
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        ScoringWeights {
+            bayesian: 2.7,
+            valid_bias: 0.1,
+            likelihood: 0.01,
+            seen_bias: -0.1,
+            coverage: 0.0,
+            yellow_placement: 0.0,
+            worst_case: 0.0,
+            blend: Blend::default(),
+            likelihood_norm: LikelihoodNorm::default(),
+            metric: Metric::default(),
+        }
+    }
+}
+
+// Number of distinct letters in the guess whose presence in the answer is still unknown
+pub fn coverage_score<const N: usize>(guess: &str, constraints: &Constraints<N>) -> f64 {
+    let mut tested = Vec::new();
+
+    for &c in guess.as_bytes() {
+        if !presence_known(c, constraints) && !tested.contains(&c) {
+            tested.push(c);
+        }
+    }
+
+    tested.len() as f64
+}
+
+fn presence_known<const N: usize>(c: u8, constraints: &Constraints<N>) -> bool {
+    constraints.known_letters.contains(&Some(c))
+        || constraints.excluded_letters.contains(&c)
+        || constraints.min_counts.get(&c).is_some_and(|&min| min > 0)
+        || constraints
+            .included_letters
+            .iter()
+            .any(|set| set.contains(&c))
+}
+
+// The guess whose distinct untried letters appear in the most candidates, counting each letter
+// by the share of candidates containing it. Letters already guessed or known either way count
+// for nothing
+pub fn best_coverage_guess<const N: usize>(
+    all_words: &[&'static str],
+    candidates: &[&str],
+    constraints: &Constraints<N>,
+    seen: &SeenLetterBitFlags,
+) -> &'static str {
+    let mut weights = [0.; 26];
+    for word in candidates {
+        let mut letters: Vec<usize> = word.chars().filter_map(letter_index).collect();
+        letters.sort_unstable();
+        letters.dedup();
+        for index in letters {
+            weights[index] += 1. / candidates.len() as f64;
+        }
+    }
+
+    let score = |word: &str| {
+        let mut tried = *seen;
+        let mut score = 0.;
+        for c in word.chars() {
+            let (Some(byte), Some(index)) = (char_to_byte(c), letter_index(c)) else {
+                continue;
+            };
+            if !tried.get(c) && !presence_known(byte, constraints) {
+                score += weights[index];
+            }
+            tried.set(c, true);
+        }
+        score
+    };
+
+    all_words
+        .par_iter()
+        .map(|&word| (word, score(word)))
+        .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(word, _)| word)
+        .expect("there is always a guess")
+}
+
+// Number of positions where the guess tries a yellow letter somewhere it hasn't been ruled out
+// yet, each of which either turns it green or rules out one more spot
+pub fn yellow_placement_score<const N: usize>(guess: &str, constraints: &Constraints<N>) -> f64 {
+    guess
+        .bytes()
+        .enumerate()
+        .filter(|&(i, c)| {
+            constraints.known_letters[i].is_none()
+                && !constraints.included_letters[i].contains(&c)
+                && constraints
+                    .included_letters
+                    .iter()
+                    .any(|set| set.contains(&c))
+        })
+        .count() as f64
+}
+
+pub fn find_guess_fitness<const N: usize>(
+    guess: &str,
+    words: &[&str],
+    probabilites: &HashMap<&str, f64>,
+    freq_data: &[HashMap<u8, f64>],
+    seen: &SeenLetterBitFlags,
+    constraints: &Constraints<N>,
+    weights: &ScoringWeights,
+) -> f64 {
+    score_breakdown(
+        guess,
+        words,
+        probabilites,
+        freq_data,
+        seen,
+        constraints,
+        weights,
+    )
+    .total
+}
+
+// Each term's weighted contribution to a guess's fitness. With a non-additive blend, the
+// bayesian term is whatever the blend adds on top of the raw entropy
+#[derive(Debug, Clone, Copy)]
+pub struct ScoreBreakdown {
+    pub entropy: f64,
+    pub bayesian: f64,
+    pub valid_bias: f64,
+    pub likelihood: f64,
+    pub seen_bias: f64,
+    pub coverage: f64,
+    pub yellow_placement: f64,
+    pub worst_case: f64,
+    pub total: f64,
+}
+
+pub fn score_breakdown<const N: usize>(
+    guess: &str,
+    words: &[&str],
+    probabilites: &HashMap<&str, f64>,
+    freq_data: &[HashMap<u8, f64>],
+    seen: &SeenLetterBitFlags,
+    constraints: &Constraints<N>,
+    weights: &ScoringWeights,
+) -> ScoreBreakdown {
+    score_with_stats(
+        guess,
+        words,
+        probabilites,
+        freq_data,
+        &weights.likelihood_norm.stats(words, freq_data),
+        seen,
+        constraints,
+        weights,
+    )
+}
+
+// score_breakdown with the likelihood stats already worked out, for scoring many guesses
+// against the same candidates
+#[allow(clippy::too_many_arguments)]
+fn score_with_stats<const N: usize>(
+    guess: &str,
+    words: &[&str],
+    probabilites: &HashMap<&str, f64>,
+    freq_data: &[HashMap<u8, f64>],
+    likelihood_stats: &LikelihoodStats,
+    seen: &SeenLetterBitFlags,
+    constraints: &Constraints<N>,
+    weights: &ScoringWeights,
+) -> ScoreBreakdown {
+    let (entropy, worst_case) = match (weights.metric, weights.worst_case != 0.0) {
+        (Metric::Entropy, true) => {
+            let report = guess_entropy_report(guess, words);
+            (report.entropy, report.max_bucket_size as f64)
+        }
+        (Metric::Buckets, true) => {
+            let report = guess_entropy_report(guess, words);
+            (
+                (report.buckets as f64).log2(),
+                report.max_bucket_size as f64,
+            )
+        }
+        (Metric::Entropy, false) => (guess_entropy(guess, words), 0.0),
+        (Metric::Buckets, false) => ((distinct_outcomes(guess, words) as f64).log2(), 0.0),
+    };
+    let bayesian = *probabilites.get(guess).unwrap_or(&0.0);
+    let valid_bias = if words.contains(&guess) { 1.0 } else { 0.0 };
+    let likelihood = weights.likelihood_norm.apply(
+        word_likelihood_score(guess, freq_data),
+        guess.len(),
+        likelihood_stats,
+    );
+    let seen_bias = seen.get_word(guess) as f64;
+    let coverage = if weights.coverage != 0.0 {
+        coverage_score(guess, constraints)
+    } else {
+        0.0
+    };
+    let yellow_placement = if weights.yellow_placement != 0.0 {
+        yellow_placement_score(guess, constraints)
+    } else {
+        0.0
+    };
+
+    let information = weights.blend.apply(entropy, bayesian, weights.bayesian);
+
+    ScoreBreakdown {
+        entropy,
+        bayesian: information - entropy,
+        valid_bias: valid_bias * weights.valid_bias,
+        likelihood: likelihood * weights.likelihood,
+        seen_bias: seen_bias * weights.seen_bias,
+        coverage: coverage * weights.coverage,
+        yellow_placement: yellow_placement * weights.yellow_placement,
+        worst_case: -worst_case * weights.worst_case,
+        total: information
+            + valid_bias * weights.valid_bias
+            + likelihood * weights.likelihood
+            + seen_bias * weights.seen_bias
+            + coverage * weights.coverage
+            + yellow_placement * weights.yellow_placement
+            - worst_case * weights.worst_case,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverError {
+    // Nothing left that could be guessed, e.g. after hard mode filtered a small guess list
+    NoGuesses,
+    // No answer is consistent with the feedback so far
+    NoCandidates,
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolverError::NoGuesses => write!(f, "no valid guesses are left"),
+            SolverError::NoCandidates => write!(f, "no candidates match the feedback so far"),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+// Like find_best_guess, but fails instead of panicking when either list is empty
+pub fn try_find_best_guess<const N: usize>(
+    all_words: &[&'static str],
+    remaining_words: &[&'static str],
+    probabilites: &HashMap<&str, f64>,
+    freq_data: &[HashMap<u8, f64>],
+    seen: &SeenLetterBitFlags,
+    constraints: &Constraints<N>,
+    weights: &ScoringWeights,
+) -> Result<&'static str, SolverError> {
+    if remaining_words.is_empty() {
+        return Err(SolverError::NoCandidates);
+    }
+
+    let likelihood_stats = weights.likelihood_norm.stats(remaining_words, freq_data);
+    all_words
+        .par_iter()
+        .map(|&word| {
+            let score = score_with_stats(
+                word,
+                remaining_words,
+                probabilites,
+                freq_data,
+                &likelihood_stats,
+                seen,
+                constraints,
+                weights,
+            );
+            (word, score.total)
+        })
+        // Ties go to the alphabetically first word so the choice doesn't depend on list order
+        .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(word, _)| word)
+        .ok_or(SolverError::NoGuesses)
+}
+
+// The `count` fittest guesses, best first, ordered like find_best_guess breaks ties
+#[allow(clippy::too_many_arguments)]
+pub fn find_best_guesses<const N: usize>(
+    all_words: &[&'static str],
+    remaining_words: &[&'static str],
+    probabilites: &HashMap<&str, f64>,
+    freq_data: &[HashMap<u8, f64>],
+    seen: &SeenLetterBitFlags,
+    constraints: &Constraints<N>,
+    weights: &ScoringWeights,
+    count: usize,
+) -> Vec<(&'static str, f64)> {
+    let likelihood_stats = weights.likelihood_norm.stats(remaining_words, freq_data);
+    let mut scored: Vec<(&'static str, f64)> = all_words
+        .par_iter()
+        .map(|&word| {
+            let score = score_with_stats(
+                word,
+                remaining_words,
+                probabilites,
+                freq_data,
+                &likelihood_stats,
+                seen,
+                constraints,
+                weights,
+            );
+            (word, score.total)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    scored.truncate(count);
+    scored
+}
+
+pub fn find_best_guess<const N: usize>(
+    all_words: &[&'static str],
+    remaining_words: &[&'static str],
+    probabilites: &HashMap<&str, f64>,
+    freq_data: &[HashMap<u8, f64>],
+    seen: &SeenLetterBitFlags,
+    constraints: &Constraints<N>,
+    weights: &ScoringWeights,
+) -> &'static str {
+    try_find_best_guess(
+        all_words,
+        remaining_words,
+        probabilites,
+        freq_data,
+        seen,
+        constraints,
+        weights,
+    )
+    .unwrap()
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WordleAnswerColor {
+    Green,
+    Yellow,
+    Gray,
+}
+
+pub struct WordLists {
+    pub guesses: Vec<&'static str>,
+    pub solutions: Vec<&'static str>,
+}
+
+impl WordLists {
+    pub fn load(guesses_path: Option<&str>, solutions_path: Option<&str>) -> Result<Self, String> {
+        let guesses = match guesses_path {
+            Some(path) => load_word_list(path)?,
+            None => parse_word_list(include_str!("guess_words.txt"))?,
+        };
+        let solutions = match solutions_path {
+            Some(path) => load_word_list(path)?,
+            None => parse_word_list(include_str!("solution_words.txt"))?,
+        };
+
+        if solutions.is_empty() {
+            return Err("The solution word list is empty".to_owned());
+        }
+
+        Ok(WordLists { guesses, solutions })
+    }
+
+    // Takes the words out of both lists so they are never guessed or used as answers. Returns
+    // how many guesses and solutions were removed
+    pub fn blacklist(&mut self, words: &[&str]) -> (usize, usize) {
+        let words: HashSet<&str> = words.iter().copied().collect();
+        let (guesses, solutions) = (self.guesses.len(), self.solutions.len());
+        self.guesses.retain(|word| !words.contains(word));
+        self.solutions.retain(|word| !words.contains(word));
+        (
+            guesses - self.guesses.len(),
+            solutions - self.solutions.len(),
+        )
+    }
+}
+
+pub fn load_word_list(path: &str) -> Result<Vec<&'static str>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read word list '{}': {}", path, err))?;
+
+    // Word lists live for the whole run, so leaking them keeps the &'static str pipeline intact
+    parse_word_list(Box::leak(contents.into_boxed_str()))
+        .map_err(|err| format!("{}: {}", path, err))
+}
+
+// Like load_word_list for another variant's dictionary, see parse_dictionary
+pub fn load_dictionary(path: &str) -> Result<Vec<&'static str>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read dictionary '{}': {}", path, err))?;
+
+    parse_dictionary(Box::leak(contents.into_boxed_str()))
+        .map_err(|err| format!("{}: {}", path, err))
+}
+
+// Blank lines and lines starting with '#' are skipped; every other line must be a valid word.
+// Uppercase letters are accepted and folded to lowercase
+pub fn parse_word_list(contents: &'static str) -> Result<Vec<&'static str>, String> {
+    parse_words(contents, Some(WORD_LENGTH))
+}
+
+// A word list for a variant with words of some other length, which is taken from the first
+// word. A solver for it is built with Solver::sized and that length
+pub fn parse_dictionary(contents: &'static str) -> Result<Vec<&'static str>, String> {
+    let words = parse_words(contents, None)?;
+    if words.is_empty() {
+        return Err("the dictionary is empty".to_owned());
+    }
+    Ok(words)
+}
+
+// Every word has to have `length` letters, or as many as the first one if that's None
+fn parse_words(
+    contents: &'static str,
+    mut length: Option<usize>,
+) -> Result<Vec<&'static str>, String> {
+    let mut words = Vec::new();
+    let mut seen = HashSet::new();
+    let mut duplicates = 0;
+
+    for (i, line) in contents.lines().enumerate() {
+        let word = line.trim();
+
+        if word.is_empty() || word.starts_with('#') {
+            continue;
+        }
+
+        if !word.bytes().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("line {}: invalid word '{}'", i + 1, word));
+        }
+
+        // A solver works with words of one length, the N of its patterns and constraints
+        let length = *length.get_or_insert(word.len());
+        if word.len() != length {
+            return Err(format!(
+                "line {}: '{}' has {} letters, only {}-letter words are supported",
+                i + 1,
+                word,
+                word.len(),
+                length
+            ));
+        }
+
+        let word = if word.bytes().any(|c| c.is_ascii_uppercase()) {
+            &*Box::leak(word.to_ascii_lowercase().into_boxed_str())
+        } else {
+            word
+        };
+
+        // Repeats would count twice in letter frequencies and pattern buckets
+        if seen.insert(word) {
+            words.push(word);
+        } else {
+            duplicates += 1;
+        }
+    }
+
+    if duplicates > 0 {
+        log::warn!("removed {} duplicate words from a word list", duplicates);
+    }
+
+    Ok(words)
+}
+
+pub const MAX_GUESSES: usize = 6;
+
+pub const WORD_LENGTH: usize = 5;
+
+pub const EXPECTED_ATTEMPTS_SAMPLE: usize = 50;
+
+pub const PENALTY_FIELD: usize = 10;
+
+pub const FOLLOW_UP_BITS: f64 = 4.6;
+
+// Hard codes the first best guess because there's no point in calculating it again every time
+pub const OPENER: &str = "salet";
+
+// The guess with the most entropy over the whole solution list. OPENER is this for the bundled
+// lists, other lists may not even have it
+pub fn best_opener(word_lists: &WordLists) -> Option<&'static str> {
+    opener_entropies(word_lists)
+        .into_iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(word, _)| word)
+}
+
+// Like best_opener, but a solution word within `margin` bits of the best wins over any guess-only
+// word. The solution list sticks to everyday words, so this trades a sliver of entropy for an
+// opener that's easy to remember and type
+pub fn common_opener(word_lists: &WordLists, margin: f64) -> Option<&'static str> {
+    let entropies = opener_entropies(word_lists);
+    let best = entropies
+        .iter()
+        .map(|&(_, entropy)| entropy)
+        .max_by(f64::total_cmp)?;
+
+    entropies
+        .into_iter()
+        .max_by(|a, b| {
+            let common = |&(word, entropy): &(&str, f64)| {
+                entropy >= best - margin && word_lists.solutions.contains(&word)
+            };
+            common(a)
+                .cmp(&common(b))
+                .then_with(|| a.1.total_cmp(&b.1))
+                .then_with(|| b.0.cmp(a.0))
+        })
+        .map(|(word, _)| word)
+}
+
+fn opener_entropies(word_lists: &WordLists) -> Vec<(&'static str, f64)> {
+    word_lists
+        .guesses
+        .par_iter()
+        .map(|&word| (word, guess_entropy(word, &word_lists.solutions)))
+        .collect()
+}
+
+// Hard mode forces later guesses to reuse what the opener found, so its opener is tuned
+// separately. Benchmarked against leant, trace, slate and crate, salet still did best
+pub const HARD_OPENER: &str = "salet";
+
+// Caps on how much of the two-ply search is expanded: the number of top one-step guesses
+// that are re-ranked, and how many of each guess's largest buckets get a follow-up search.
+#[derive(Debug, Clone, Copy)]
+pub struct Lookahead {
+    pub candidates: usize,
+    pub buckets: usize,
+}
+
+impl Default for Lookahead {
+    fn default() -> Self {
+        Lookahead {
+            candidates: 10,
+            buckets: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Strategy {
+    // Sum the entropy and the weighted terms from ScoringWeights
+    #[default]
+    Additive,
+    // Among guesses within epsilon bits of the best entropy, pick the most likely answer
+    TieBreak {
+        epsilon: f64,
+    },
+    // Play whichever of the likeliest candidate and the most informative guess is expected to
+    // finish sooner, see ev_guess
+    ExpectedValue,
+}
+
+// How the first two guesses are chosen
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OpeningStrategy {
+    // The opener, then the same search as every later turn
+    #[default]
+    Search,
+    // Whatever tries the most common letters that haven't been tried yet
+    Coverage,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SolverOptions {
+    pub hard_mode: bool,
+    pub weights: ScoringWeights,
+    pub lookahead: Option<Lookahead>,
+    pub strategy: Strategy,
+    // Keep the letter frequencies of the full solution list instead of recomputing them from
+    // the remaining candidates every turn
+    pub global_frequency: bool,
+    // Cross-check every filtering step against simulate_guess and log any words the
+    // constraints keep or drop wrongly
+    pub verify_filter: bool,
+    // Openers to use instead of OPENER and HARD_OPENER
+    pub first_guess: Option<&'static str>,
+    pub first_guess_hard: Option<&'static str>,
+    // Yellows don't rule the letter out at their own position
+    pub lenient_yellow: bool,
+    pub opening_strategy: OpeningStrategy,
+    // Forget which letters were seen every this many turns, keeping only the latest guess
+    pub seen_decay: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SolveResult<const N: usize = WORD_LENGTH> {
+    // Entropy in bits of each guess against the candidates left when it was made
+    pub entropies: Vec<f64>,
+    pub game: Game<N>,
+}
+
+impl<const N: usize> SolveResult<N> {
+    pub fn guesses(&self) -> Vec<&str> {
+        self.game
+            .turns()
+            .iter()
+            .map(|turn| turn.guess.as_str())
+            .collect()
+    }
+
+    pub fn solved(&self) -> bool {
+        self.game.solved()
+    }
+
+    // Counts the whole game, including any turns played before the solve started
+    pub fn attempts(&self) -> usize {
+        self.game.attempts()
+    }
+
+    // An unlimited solve that only found the answer past the limit would still have lost
+    pub fn failed(&self) -> bool {
+        !self.solved() || self.attempts() > MAX_GUESSES
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepResult {
+    pub guess: &'static str,
+    // None once the game is over or there is nothing left to guess
+    pub next_guess: Option<&'static str>,
+    pub remaining: usize,
+    pub solved: bool,
+}
+
+// Where the colors for each guess come from when the solver plays a whole game
+pub trait FeedbackSource<const N: usize = WORD_LENGTH> {
+    fn feedback(&mut self, guess: &str) -> [WordleAnswerColor; N];
+}
+
+// A known answer, scored like Wordle would
+pub struct Secret<'a>(pub &'a str);
+
+impl<const N: usize> FeedbackSource<N> for Secret<'_> {
+    fn feedback(&mut self, guess: &str) -> [WordleAnswerColor; N] {
+        simulate_guess(self.0, guess)
+    }
+}
+
+// Best guesses keyed by the guesses and patterns that led to them. Solvers built from the same
+// word lists and options reach the same state from the same history, so clones of one solver
+// can share this, e.g. across a benchmark
+type History<const N: usize> = Vec<(String, [WordleAnswerColor; N])>;
+
+#[derive(Debug, Default)]
+pub struct GuessCache<const N: usize = WORD_LENGTH> {
+    entries: Mutex<HashMap<History<N>, &'static str>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    miss_nanos: AtomicU64,
+}
+
+// The best second guess after the opener for each pattern it can get, built once per mode
+// since hard mode limits which second guesses are allowed
+#[derive(Debug, Clone)]
+pub struct OpeningBook<const N: usize = WORD_LENGTH> {
+    pub opener: &'static str,
+    pub hard_mode: bool,
+    entries: BTreeMap<[WordleAnswerColor; N], &'static str>,
+}
+
+impl<const N: usize> OpeningBook<N> {
+    pub fn second_guess(&self, pattern: &[WordleAnswerColor; N]) -> Option<&'static str> {
+        self.entries.get(pattern).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    // Average time spent choosing a guess on a miss, multiplied by the number of hits
+    pub estimated_seconds_saved: f64,
+}
+
+impl GuessCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<const N: usize> GuessCache<N> {
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let miss_seconds = self.miss_nanos.load(Ordering::Relaxed) as f64 / 1e9;
+
+        CacheStats {
+            hits,
+            misses,
+            estimated_seconds_saved: if misses > 0 {
+                miss_seconds / misses as f64 * hits as f64
+            } else {
+                0.
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Turn<const N: usize = WORD_LENGTH> {
+    pub guess: String,
+    #[cfg_attr(feature = "serde", serde(with = "serde_impls::pattern"))]
+    pub pattern: [WordleAnswerColor; N],
+    pub remaining_before: usize,
+    pub remaining_after: usize,
+}
+
+// Every guess made so far along with its feedback and how far it narrowed the candidates
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Game<const N: usize = WORD_LENGTH> {
+    turns: Vec<Turn<N>>,
+}
+
+// A puzzle in progress as written by Solver::saved_game. The constraints and seen letters are
+// kept along with the history since 'exclude', 'require' and 'not' change them without a turn
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SavedGame<const N: usize = WORD_LENGTH> {
+    pub game: Game<N>,
+    pub constraints: Constraints<N>,
+    pub seen: SeenLetterBitFlags,
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<const N: usize> Default for Game<N> {
+    fn default() -> Self {
+        Game { turns: Vec::new() }
+    }
+}
+
+impl<const N: usize> Game<N> {
+    pub fn push(&mut self, turn: Turn<N>) {
+        self.turns.push(turn);
+    }
+
+    pub fn turns(&self) -> &[Turn<N>] {
+        &self.turns
+    }
+
+    pub fn attempts(&self) -> usize {
+        self.turns.len()
+    }
+
+    pub fn solved(&self) -> bool {
+        self.turns.last().is_some_and(|turn| {
+            turn.pattern
+                .iter()
+                .all(|&color| color == WordleAnswerColor::Green)
+        })
+    }
+
+    // Bits each turn gained, log2 of how many times it narrowed the candidates. Over a solved
+    // game these add up to log2 of the candidate count before the first guess
+    pub fn information(&self) -> Vec<f64> {
+        self.turns
+            .iter()
+            .map(|turn| (turn.remaining_before as f64 / turn.remaining_after.max(1) as f64).log2())
+            .collect()
+    }
+
+    // Checked against every earlier turn, since each one's hints stay binding
+    pub fn hard_mode_violation(&self, guess: &str) -> Option<HardModeViolation> {
+        self.turns
+            .iter()
+            .find_map(|turn| hard_mode_violation(guess, &turn.guess, &turn.pattern))
+    }
+
+    pub fn share_grid(&self) -> String {
+        let patterns: Vec<[WordleAnswerColor; N]> =
+            self.turns.iter().map(|turn| turn.pattern).collect();
+        share_grid(&patterns, self.solved())
+    }
+}
+
+// The most entropy any guess gets against a set of candidates, kept for one turn of one solve.
+// Lookahead scores every guess against the buckets of each of its top guesses, and different
+// top guesses often split off the same bucket. Sets are told apart by their hash
+#[derive(Debug, Default)]
+struct EntropyCache {
+    entries: Mutex<HashMap<u64, f64>>,
+}
+
+// A clone is a separate solve, so it starts with an empty cache
+impl Clone for EntropyCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl EntropyCache {
+    fn best_entropy(&self, guesses: &[&'static str], candidates: &[&str]) -> f64 {
+        let mut hasher = DefaultHasher::new();
+        candidates.hash(&mut hasher);
+        let key = hasher.finish();
+
+        if let Some(&entropy) = self.entries.lock().unwrap().get(&key) {
+            return entropy;
+        }
+
+        let entropy = guesses
+            .par_iter()
+            .map(|&word| guess_entropy(word, candidates))
+            .max_by(f64::total_cmp)
+            .unwrap_or(0.);
+        self.entries.lock().unwrap().insert(key, entropy);
+        entropy
+    }
+
+    fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Solver<const N: usize = WORD_LENGTH> {
+    all_words: Vec<&'static str>,
+    candidates: Vec<&'static str>,
+    constraints: Constraints<N>,
+    probabilities: HashMap<&'static str, f64>,
+    freq_data: [HashMap<u8, f64>; N],
+    // Kept in step with the candidates and freq_data by refilter
+    likelihood_stats: LikelihoodStats,
+    seen: SeenLetterBitFlags,
+    options: SolverOptions,
+    turn: usize,
+    // The precomputed opener only applies while nothing is known about the answer
+    use_opener: bool,
+    game: Game<N>,
+    cache: Option<Arc<GuessCache<N>>>,
+    book: Option<Arc<OpeningBook<N>>>,
+    entropy_cache: EntropyCache,
+    // Fitness taken off guesses the player has already been shown, e.g. in earlier puzzles
+    penalties: HashMap<&'static str, f64>,
+    // Keeps playing past MAX_GUESSES instead of giving up, for analysis
+    unlimited: bool,
+    // Cleared once the state has been changed by anything other than a full pattern, since the
+    // game history no longer identifies it
+    cacheable: bool,
+    // Cleared once the candidates have been narrowed by something the constraints can't record,
+    // like count feedback or a regex, so a saved game would no longer rebuild them
+    savable: bool,
+    // The guess step will play, once chosen. Cleared whenever the state changes
+    pending_guess: Option<&'static str>,
+}
+
+// Words the filter kept but shouldn't have, and words it dropped but shouldn't have
+fn filter_mismatch<'a>(
+    candidates: &[&'a str],
+    expected: &[&'a str],
+) -> (Vec<&'a str>, Vec<&'a str>) {
+    let kept = candidates
+        .iter()
+        .copied()
+        .filter(|word| !expected.contains(word))
+        .collect();
+    let dropped = expected
+        .iter()
+        .copied()
+        .filter(|word| !candidates.contains(word))
+        .collect();
+    (kept, dropped)
+}
+
+impl Solver {
+    pub fn new(word_lists: &WordLists, options: SolverOptions) -> Solver {
+        Self::sized(word_lists, options)
+    }
+}
+
+impl<const N: usize> Solver<N> {
+    // Solver::new for words of another length, which is the solver's N. Every word in both
+    // lists has to have N letters
+    pub fn sized(word_lists: &WordLists, options: SolverOptions) -> Self {
+        debug_assert!(word_lists
+            .guesses
+            .iter()
+            .chain(&word_lists.solutions)
+            .all(|word| word.len() == N));
+        let candidates = word_lists.solutions.clone();
+        let freq_data = letter_frequency(&candidates);
+        let likelihood_stats = options
+            .weights
+            .likelihood_norm
+            .stats(&candidates, &freq_data);
+
+        Solver {
+            all_words: word_lists.guesses.clone(),
+            candidates,
+            constraints: Constraints::default(),
+            probabilities: HashMap::new(),
+            freq_data,
+            likelihood_stats,
+            seen: SeenLetterBitFlags::new(),
+            options,
+            turn: 0,
+            use_opener: true,
+            game: Game::default(),
+            cache: None,
+            book: None,
+            entropy_cache: EntropyCache::default(),
+            penalties: HashMap::new(),
+            unlimited: false,
+            cacheable: true,
+            savable: true,
+            pending_guess: None,
+        }
+    }
+
+    /// The solution words still consistent with every piece of feedback applied so far
+    ///
+    /// ```
+    /// use ideal_wordle_player::{parse_pattern, Solver, SolverOptions, WordLists};
+    ///
+    /// let word_lists = WordLists {
+    ///     guesses: vec!["salet", "crate", "trace", "slate"],
+    ///     solutions: vec!["crate", "trace", "slate"],
+    /// };
+    /// let mut solver = Solver::new(&word_lists, SolverOptions::default());
+    /// assert_eq!(solver.candidates().len(), 3);
+    ///
+    /// solver.apply_feedback("salet", parse_pattern("xyxyy").unwrap());
+    /// assert_eq!(solver.candidates(), ["crate", "trace"]);
+    /// ```
+    pub fn candidates(&self) -> &[&'static str] {
+        &self.candidates
+    }
+
+    // Every word that can still be guessed, which in hard mode is only those that fit the
+    // constraints
+    pub fn guesses(&self) -> &[&'static str] {
+        &self.all_words
+    }
+
+    pub fn constraints(&self) -> &Constraints<N> {
+        &self.constraints
+    }
+
+    // Per-position letter frequencies the scoring uses, over the candidates unless
+    // global_frequency keeps them at the full solution list
+    pub fn frequencies(&self) -> &[HashMap<u8, f64>; N] {
+        &self.freq_data
+    }
+
+    // Turns given through apply_feedback; count-only feedback has no pattern to record
+    pub fn game(&self) -> &Game<N> {
+        &self.game
+    }
+
+    pub fn top_guesses(&self, count: usize) -> Vec<(&'static str, f64)> {
+        find_best_guesses(
+            &self.all_words,
+            &self.candidates,
+            &self.probabilities,
+            &self.freq_data,
+            &self.seen,
+            &self.constraints,
+            &self.options.weights,
+            count,
+        )
+    }
+
+    // Remaining candidates ordered from most to least likely, with their probabilities
+    pub fn ranked_candidates(&self) -> Vec<(&'static str, f64)> {
+        let uniform = 1. / self.candidates.len() as f64;
+        let mut ranked: Vec<(&'static str, f64)> = self
+            .candidates
+            .iter()
+            .map(|&word| (word, *self.probabilities.get(word).unwrap_or(&uniform)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked
+    }
+
+    // The most patterns any allowed guess can get against the candidates
+    pub fn max_outcomes(&self) -> usize {
+        self.all_words
+            .par_iter()
+            .map(|&word| distinct_outcomes(word, &self.candidates))
+            .max()
+            .unwrap_or(0)
+    }
+
+    // In hard mode every guess has to fit what's known, which can leave too few ways to tell the
+    // candidates apart in the guesses left, e.g. after "hatch" comes back xgggg. Returns how
+    // many candidates the guesses left could be guaranteed to sort out when that's fewer than
+    // there are. The bound assumes every later guess splits as well as the best one now can:
+    // a candidate solves one and splits the rest, any other guess only splits
+    pub fn hard_mode_risk(&self) -> Option<usize> {
+        if !self.options.hard_mode || self.candidates.len() <= 1 {
+            return None;
+        }
+
+        let guesses_left = MAX_GUESSES.saturating_sub(self.turn);
+        let capacity = |best_candidate: usize, best_other: usize| {
+            let mut capacity: usize = 1;
+            for _ in 1..guesses_left {
+                capacity = (1 + best_candidate.saturating_sub(1).saturating_mul(capacity))
+                    .max(best_other.saturating_mul(capacity));
+            }
+            capacity
+        };
+        let outcomes = |word: &'static str| {
+            let outcomes = distinct_outcomes(word, &self.candidates);
+            if self.probabilities.contains_key(word) {
+                (outcomes, 0)
+            } else {
+                (0, outcomes)
+            }
+        };
+
+        // Usually some guess is enough by itself, which saves scoring all of them
+        let enough = |(best_candidate, best_other)| {
+            capacity(best_candidate, best_other) >= self.candidates.len()
+        };
+        if self
+            .all_words
+            .par_iter()
+            .any(|&word| enough(outcomes(word)))
+        {
+            return None;
+        }
+
+        let (best_candidate, best_other) = self
+            .all_words
+            .par_iter()
+            .map(|&word| outcomes(word))
+            .reduce(|| (0, 0), |a, b| (a.0.max(b.0), a.1.max(b.1)));
+        let capacity = capacity(best_candidate, best_other);
+        (capacity < self.candidates.len()).then_some(capacity)
+    }
+
+    // Probability-weighted number of further guesses the solver would need from here, found by
+    // playing out the most likely candidates. Large candidate sets are capped to the
+    // EXPECTED_ATTEMPTS_SAMPLE most likely words, with their weights renormalized
+    pub fn expected_attempts(&self) -> f64 {
+        let sample: Vec<(&'static str, f64)> = self
+            .ranked_candidates()
+            .into_iter()
+            .take(EXPECTED_ATTEMPTS_SAMPLE)
+            .collect();
+        let total: f64 = sample.iter().map(|&(_, probability)| probability).sum();
+
+        if sample.is_empty() || total <= 0. {
+            return 0.;
+        }
+
+        let weighted: Vec<f64> = sample
+            .par_iter()
+            .map(|&(word, probability)| {
+                // The solve carries on this game, so the turns so far are taken back off
+                let attempts = self.clone().solve(word).attempts() - self.game.attempts();
+                probability * attempts as f64
+            })
+            .collect();
+
+        weighted.into_iter().sum::<f64>() / total
+    }
+
+    // Number of guesses whose feedback has been applied
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn score_breakdown(&self, guess: &str) -> ScoreBreakdown {
+        score_with_stats(
+            guess,
+            &self.candidates,
+            &self.probabilities,
+            &self.freq_data,
+            &self.likelihood_stats,
+            &self.seen,
+            &self.constraints,
+            &self.options.weights,
+        )
+    }
+
+    // Why a word is no longer a candidate, or None if it still is
+    pub fn elimination_reason(&self, word: &str) -> Option<String> {
+        if self.candidates.contains(&word) {
+            return None;
+        }
+
+        // Words that pass every constraint were either never in the solution list or were
+        // dropped by letter-count feedback, which isn't kept as a constraint
+        Some(
+            self.constraints
+                .violation(word)
+                .unwrap_or_else(|| "not one of the possible answers".to_owned()),
+        )
+    }
+
+    // Shares best guesses between this solver and every clone made from it afterwards
+    pub fn enable_cache(&mut self) -> Arc<GuessCache<N>> {
+        self.cache.get_or_insert_with(Default::default).clone()
+    }
+
+    pub fn set_cache(&mut self, cache: Arc<GuessCache<N>>) {
+        self.cache = Some(cache);
+    }
+
+    pub fn set_book(&mut self, book: Arc<OpeningBook<N>>) {
+        self.book = Some(book);
+    }
+
+    pub fn set_penalties(&mut self, penalties: HashMap<&'static str, f64>) {
+        self.penalties = penalties;
+        self.pending_guess = None;
+    }
+
+    // Past the limit every guess is the likeliest candidate, so an unlimited game still ends
+    pub fn set_unlimited(&mut self, unlimited: bool) {
+        self.unlimited = unlimited;
+    }
+
+    // best_guess for states that may have run out of words, e.g. from user input
+    pub fn try_best_guess(&self) -> Result<&'static str, SolverError> {
+        if self.candidates.is_empty() {
+            Err(SolverError::NoCandidates)
+        } else if self.all_words.is_empty() {
+            Err(SolverError::NoGuesses)
+        } else {
+            Ok(self.best_guess())
+        }
+    }
+
+    pub fn best_guess(&self) -> &'static str {
+        let guess = match &self.cache {
+            Some(cache) if self.cacheable && self.opener().is_none() => self.cached_guess(cache),
+            _ => self.choose_guess(),
+        };
+        let guess = self.apply_penalties(guess);
+
+        if log::log_enabled!(log::Level::Debug) {
+            log::debug!(
+                "turn {}: chose '{}' with {:.3} bits of entropy over {} candidates",
+                self.turn + 1,
+                guess,
+                guess_entropy(guess, &self.candidates),
+                self.candidates.len()
+            );
+        }
+
+        guess
+    }
+
+    // A penalized guess is swapped for whichever of the PENALTY_FIELD fittest guesses scores best
+    // once penalties are taken off, so only close calls change. The guess the solver chose
+    // counts as the fittest even when it isn't, as with the opener or lookahead. With two
+    // candidates or fewer left, guessing one of them is still best
+    fn apply_penalties(&self, guess: &'static str) -> &'static str {
+        let penalty = |word: &str| self.penalties.get(word).copied().unwrap_or(0.);
+        if penalty(guess) <= 0. || self.candidates.len() <= 2 {
+            return guess;
+        }
+
+        let top = self.top_guesses(PENALTY_FIELD);
+        let best = top.first().map_or(0., |&(_, fitness)| fitness);
+        let fitness = self.score_breakdown(guess).total;
+
+        top.into_iter()
+            .filter(|&(word, _)| word != guess)
+            .chain(std::iter::once((guess, fitness.max(best))))
+            .map(|(word, fitness)| (word, fitness - penalty(word)))
+            .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map_or(guess, |(word, _)| word)
+    }
+
+    fn cached_guess(&self, cache: &GuessCache<N>) -> &'static str {
+        let key: History<N> = self
+            .game
+            .turns()
+            .iter()
+            .map(|turn| (turn.guess.clone(), turn.pattern))
+            .collect();
+
+        if let Some(&guess) = cache.entries.lock().unwrap().get(&key) {
+            cache.hits.fetch_add(1, Ordering::Relaxed);
+            return guess;
+        }
+
+        // The lock isn't held while choosing, so two threads may both compute the same entry
+        let start = Instant::now();
+        let guess = self.choose_guess();
+        cache
+            .miss_nanos
+            .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        cache.misses.fetch_add(1, Ordering::Relaxed);
+        cache.entries.lock().unwrap().insert(key, guess);
+
+        guess
+    }
+
+    // The fixed first guess, while it still applies. Like any other guess it is checked against
+    // the answer by the caller, so an answer equal to the opener is a one-guess solve.
+    pub fn opener(&self) -> Option<&'static str> {
+        let opener = if self.options.hard_mode {
+            self.options.first_guess_hard.unwrap_or(HARD_OPENER)
+        } else {
+            self.options.first_guess.unwrap_or(OPENER)
+        };
+        self.use_opener.then_some(opener)
+    }
+
+    fn choose_guess(&self) -> &'static str {
+        if self.options.opening_strategy == OpeningStrategy::Coverage
+            && self.turn < 2
+            && self.candidates.len() > 2
+        {
+            best_coverage_guess(
+                &self.all_words,
+                &self.candidates,
+                &self.constraints,
+                &self.seen,
+            )
+        } else if let Some(opener) = self.opener() {
+            opener
+        } else if let Some(guess) = self.book_guess() {
+            guess
+        } else if let Strategy::ExpectedValue = self.options.strategy {
+            ev_guess(
+                &self.candidates,
+                &self.probabilities,
+                &self.all_words,
+                MAX_GUESSES.saturating_sub(self.turn),
+            )
+        } else if self.candidates.len() <= 2 || self.turn + 1 >= MAX_GUESSES {
+            self.candidates
+                .iter()
+                .max_by_key(|&word| {
+                    ordered_float::OrderedFloat(*self.probabilities.get(*word).unwrap_or(&0.0))
+                })
+                .unwrap()
+        } else if let Some(probe) = self.endgame_guess() {
+            probe
+        } else if let Some(lookahead) = &self.options.lookahead {
+            self.lookahead_guess(lookahead)
+        } else if let Strategy::TieBreak { epsilon } = self.options.strategy {
+            self.tie_break_guess(epsilon)
+        } else {
+            find_best_guess(
+                &self.all_words,
+                &self.candidates,
+                &self.probabilities,
+                &self.freq_data,
+                &self.seen,
+                &self.constraints,
+                &self.options.weights,
+            )
+        }
+    }
+
+    // Only the opener's pattern may be known, the book can't account for anything else
+    fn book_guess(&self) -> Option<&'static str> {
+        let book = self.book.as_ref()?;
+        match self.game.turns() {
+            [turn]
+                if self.cacheable
+                    && book.hard_mode == self.options.hard_mode
+                    && turn.guess == book.opener =>
+            {
+                book.second_guess(&turn.pattern)
+            }
+            _ => None,
+        }
+    }
+
+    // Candidates that agree everywhere but one position (fight, light, might, ...) can't all be
+    // tried one by one once there are more of them than guesses left. A probe that tells as many
+    // of them apart as possible is better then. A pair is still guessed directly since that
+    // always finishes within two guesses, which is all a probe could promise
+    fn endgame_guess(&self) -> Option<&'static str> {
+        let guesses_left = MAX_GUESSES - self.turn;
+        if self.candidates.len() <= 2 || self.candidates.len() <= guesses_left {
+            return None;
+        }
+
+        let first = self.candidates[0].as_bytes();
+        let differing: Vec<usize> = (0..N)
+            .filter(|&i| {
+                self.candidates
+                    .iter()
+                    .any(|word| word.as_bytes()[i] != first[i])
+            })
+            .collect();
+        if differing.len() != 1 {
+            return None;
+        }
+
+        self.all_words
+            .par_iter()
+            .map(|&word| (word, distinct_outcomes(word, &self.candidates)))
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(word, _)| word)
+    }
+
+    fn tie_break_guess(&self, epsilon: f64) -> &'static str {
+        let entropies: Vec<(&'static str, f64)> = self
+            .all_words
+            .par_iter()
+            .map(|&word| (word, guess_entropy(word, &self.candidates)))
+            .collect();
+
+        let best = entropies
+            .iter()
+            .map(|&(_, entropy)| entropy)
+            .max_by(f64::total_cmp)
+            .unwrap();
+
+        entropies
+            .into_iter()
+            .filter(|&(_, entropy)| best - entropy <= epsilon)
+            .map(|(word, entropy)| {
+                let probability = *self.probabilities.get(word).unwrap_or(&0.0);
+                (word, probability, entropy)
+            })
+            .max_by(|a, b| {
+                a.1.total_cmp(&b.1)
+                    .then_with(|| a.2.total_cmp(&b.2))
+                    .then_with(|| b.0.cmp(a.0))
+            })
+            .unwrap()
+            .0
+    }
+
+    // Re-ranks the top one-step guesses by adding the information the best follow-up guess
+    // would gain in each of their largest buckets. Smaller buckets are assumed to be fully
+    // resolved by the next guess.
+    fn lookahead_guess(&self, lookahead: &Lookahead) -> &'static str {
+        let mut scored: Vec<(&'static str, f64)> = self
+            .all_words
+            .par_iter()
+            .map(|&word| (word, self.score_breakdown(word).total))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        scored.truncate(lookahead.candidates.max(1));
+
+        let total = self.candidates.len() as f64;
+
+        scored
+            .into_iter()
+            .map(|(guess, fitness)| {
+                let mut buckets: HashMap<usize, Vec<&'static str>> = HashMap::new();
+                for &word in &self.candidates {
+                    if word != guess {
+                        buckets
+                            .entry(pattern_code(word, guess))
+                            .or_default()
+                            .push(word);
+                    }
+                }
+
+                let mut buckets: Vec<Vec<&'static str>> = buckets.into_values().collect();
+                buckets.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+                let follow_up: f64 = buckets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, bucket)| {
+                        let p = bucket.len() as f64 / total;
+                        let information = if i < lookahead.buckets && bucket.len() > 2 {
+                            self.entropy_cache.best_entropy(&self.all_words, bucket)
+                        } else {
+                            (bucket.len() as f64).log2()
+                        };
+                        p * information
+                    })
+                    .sum();
+
+                (guess, fitness + follow_up)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .unwrap()
+            .0
+    }
+
+    pub fn apply_feedback(&mut self, guess: &str, output: [WordleAnswerColor; N]) {
+        let remaining_before = self.candidates.len();
+        // simulate_guess always gives strict yellows, so lenient ones can't be checked against it
+        let verify = self.options.verify_filter && !self.options.lenient_yellow;
+        let expected: Option<Vec<&'static str>> = verify.then(|| {
+            self.candidates
+                .iter()
+                .copied()
+                .filter(|word| simulate_guess(word, guess) == output)
+                .collect()
+        });
+
+        if self.options.lenient_yellow {
+            self.constraints.update_from_lenient_guess(guess, output);
+        } else {
+            self.constraints.update_from_guess(guess, output);
+        }
+        self.mark_seen(guess);
+        self.turn += 1;
+
+        self.refilter();
+
+        if let Some(expected) = expected {
+            self.verify_filter(guess, output, &expected);
+        }
+
+        self.game.push(Turn {
+            guess: guess.to_owned(),
+            pattern: output,
+            remaining_before,
+            remaining_after: self.candidates.len(),
+        });
+    }
+
+    // Narrows the candidates using only how many letters were green and yellow, for clones
+    // that don't reveal which positions they were
+    pub fn apply_count_feedback(&mut self, guess: &str, feedback: (u8, u8)) {
+        self.cacheable = false;
+        self.savable = false;
+        self.mark_seen(guess);
+        self.turn += 1;
+
+        self.candidates
+            .retain(|&word| count_feedback(word, guess) == feedback);
+        self.refilter();
+    }
+
+    fn mark_seen(&mut self, guess: &str) {
+        if let Some(turns) = self.options.seen_decay {
+            if self.turn > 0 && self.turn.is_multiple_of(turns) {
+                self.seen.decay();
+            }
+        }
+        self.seen.set_word(guess, true);
+    }
+
+    pub fn exclude_letters(&mut self, letters: &[u8]) {
+        self.cacheable = false;
+        for &c in letters {
+            self.constraints.exclude_letter(c);
+        }
+
+        self.refilter();
+    }
+
+    pub fn exclude_at(&mut self, position: usize, c: u8) {
+        self.cacheable = false;
+        self.constraints.exclude_at(position, c);
+        self.refilter();
+    }
+
+    pub fn require_letters(&mut self, letters: &[u8]) {
+        self.cacheable = false;
+        for &c in letters {
+            self.constraints.require_letter(c);
+        }
+
+        self.refilter();
+    }
+
+    // Narrows the candidates by outside knowledge the constraints can't express
+    pub fn retain_candidates(&mut self, keep: impl Fn(&str) -> bool) {
+        self.cacheable = false;
+        self.savable = false;
+        self.candidates.retain(|word| keep(word));
+        self.refilter();
+    }
+
+    // Everything needed to pick this puzzle up again with restore
+    #[cfg(feature = "serde")]
+    pub fn saved_game(&self) -> Result<SavedGame<N>, String> {
+        if !self.savable {
+            return Err(
+                "the candidates were narrowed by count feedback or a filter, which a saved game can't record"
+                    .to_owned(),
+            );
+        }
+
+        Ok(SavedGame {
+            game: self.game.clone(),
+            constraints: self.constraints.clone(),
+            seen: self.seen,
+        })
+    }
+
+    // Replays the saved turns on a new solver, which checks they fit together and rebuilds the
+    // candidates, then puts back the saved constraints and seen letters on top of them
+    #[cfg(feature = "serde")]
+    pub fn restore(&mut self, saved: SavedGame<N>) -> Result<(), String> {
+        if self.turn > 0 {
+            return Err("a saved game can only be restored into a new solver".to_owned());
+        }
+
+        let turns: Vec<(String, [WordleAnswerColor; N])> = saved
+            .game
+            .turns()
+            .iter()
+            .map(|turn| (turn.guess.clone(), turn.pattern))
+            .collect();
+        validate_game(&turns, &self.candidates).map_err(|err| err.to_string())?;
+        for (guess, pattern) in &turns {
+            self.apply_feedback(guess, *pattern);
+        }
+
+        if saved.constraints != self.constraints {
+            self.cacheable = false;
+            self.constraints = saved.constraints;
+            self.refilter();
+        }
+        self.seen = saved.seen;
+
+        if self.candidates.is_empty() {
+            return Err("no answer fits the saved game".to_owned());
+        }
+        Ok(())
+    }
+
+    fn verify_filter(
+        &self,
+        guess: &str,
+        output: [WordleAnswerColor; N],
+        expected: &[&'static str],
+    ) {
+        let (kept, dropped) = filter_mismatch(&self.candidates, expected);
+        if !kept.is_empty() || !dropped.is_empty() {
+            log::warn!(
+                "filter mismatch after '{} {}': wrongly kept [{}], wrongly dropped [{}]",
+                guess,
+                pattern_string(&output),
+                kept.join(" "),
+                dropped.join(" ")
+            );
+        }
+    }
+
+    fn refilter(&mut self) {
+        self.use_opener = false;
+        self.pending_guess = None;
+        self.entropy_cache.clear();
+
+        let constraints = &self.constraints;
+        self.candidates.retain(|&word| constraints.matches(word));
+        if self.options.hard_mode {
+            self.all_words.retain(|&word| constraints.matches(word));
+        }
+
+        self.probabilities = update_word_probabilities(&self.candidates, &self.constraints);
+        if !self.options.global_frequency {
+            self.freq_data = letter_frequency(&self.candidates);
+        }
+        self.likelihood_stats = self
+            .options
+            .weights
+            .likelihood_norm
+            .stats(&self.candidates, &self.freq_data);
+
+        log::trace!(
+            "{} candidates and {} guesses remain",
+            self.candidates.len(),
+            self.all_words.len()
+        );
+    }
+
+    // Plays against a known answer until it's found or the guesses run out. The result only
+    // depends on the solver state and the answer, which is what allows the benchmark to
+    // solve words in parallel and still match a sequential run exactly.
+    pub fn solve(self, answer: &str) -> SolveResult<N> {
+        self.play(&mut Secret(answer))
+    }
+
+    // Like solve, with the colors for every guess coming from `source`
+    pub fn play(mut self, source: &mut dyn FeedbackSource<N>) -> SolveResult<N> {
+        let mut entropies = Vec::new();
+        let mut guess = self.best_guess();
+
+        loop {
+            entropies.push(guess_entropy_report(guess, &self.candidates).entropy);
+
+            let step = self.advance(guess, source.feedback(guess));
+            match step.next_guess {
+                Some(next_guess) if !step.solved => guess = next_guess,
+                _ => {
+                    return SolveResult {
+                        entropies,
+                        game: self.game,
+                    }
+                }
+            }
+        }
+    }
+
+    // The guess the next step will play. It's only searched for once per state, so calling this
+    // before step, or after a step that already chose it, costs nothing extra
+    pub fn guess(&mut self) -> Result<&'static str, SolverError> {
+        match self.pending_guess {
+            Some(guess) => Ok(guess),
+            None => {
+                let guess = self.try_best_guess()?;
+                self.pending_guess = Some(guess);
+                Ok(guess)
+            }
+        }
+    }
+
+    /// Plays the pending guess and applies the feedback it got, for callers that drive the
+    /// game themselves one turn at a time. The returned next guess is the one the following
+    /// step will play
+    ///
+    /// ```
+    /// use ideal_wordle_player::{feedback, Solver, SolverOptions, WordLists};
+    ///
+    /// let word_lists = WordLists {
+    ///     guesses: vec!["salet", "crate", "trace", "slate"],
+    ///     solutions: vec!["crate", "trace", "slate"],
+    /// };
+    /// let mut solver = Solver::new(&word_lists, SolverOptions::default());
+    ///
+    /// let mut played = Vec::new();
+    /// loop {
+    ///     let guess = solver.guess().unwrap();
+    ///     let step = solver.step(feedback("crate", guess).unwrap()).unwrap();
+    ///     played.push(step.guess);
+    ///     if step.solved {
+    ///         break;
+    ///     }
+    ///     assert_eq!(step.next_guess, Some(solver.guess().unwrap()));
+    /// }
+    ///
+    /// assert_eq!(played[0], "salet");
+    /// assert_eq!(played.last(), Some(&"crate"));
+    /// ```
+    pub fn step(&mut self, feedback: [WordleAnswerColor; N]) -> Result<StepResult, SolverError> {
+        let guess = self.guess()?;
+        Ok(self.advance(guess, feedback))
+    }
+
+    fn advance(&mut self, guess: &'static str, feedback: [WordleAnswerColor; N]) -> StepResult {
+        let solved = feedback == [WordleAnswerColor::Green; N];
+        if solved {
+            // Filtering can't tell us anything more once it's solved
+            self.pending_guess = None;
+            self.game.push(Turn {
+                guess: guess.to_owned(),
+                pattern: feedback,
+                remaining_before: self.candidates.len(),
+                remaining_after: 1,
+            });
+        } else {
+            self.apply_feedback(guess, feedback);
+        }
+
+        let next_guess = if solved || (!self.unlimited && self.game.attempts() >= MAX_GUESSES) {
+            None
+        } else {
+            self.guess().ok()
+        };
+
+        StepResult {
+            guess,
+            next_guess,
+            remaining: if solved { 1 } else { self.candidates.len() },
+            solved,
+        }
+    }
+}
+
+pub fn update_word_probabilities<const N: usize>(
+    words: &[&'static str],
+    constraints: &Constraints<N>,
+) -> HashMap<&'static str, f64> {
+    let mut probabilities = HashMap::new();
+
+    for &word in words {
+        let mut score = 1.0;
+        let word_bytes = word.as_bytes();
+
+        for (i, c) in word_bytes.iter().enumerate() {
+            if let Some(known) = constraints.known_letters[i] {
+                if *c == known {
+                    score *= 1.5; // Boost words matching known letters
+                }
+            }
+            if constraints.included_letters.iter().any(|v| v.contains(c)) {
+                score *= 1.2; // Boost words containing useful letters
+            }
+            if constraints.excluded_letters.contains(c) {
+                score *= 0.1; // Penalize words containing eliminated letters
+            }
+        }
+
+        probabilities.insert(word, score);
+    }
+
+    // Normalize scores into probabilities
+    let total: f64 = words.iter().map(|word| probabilities[word]).sum();
+    probabilities.iter_mut().for_each(|(_, v)| *v /= total);
+
+    probabilities
+}
+
+pub fn letter_frequency<const N: usize>(words: &[&str]) -> [HashMap<u8, f64>; N] {
+    let mut frequency: [HashMap<u8, f64>; N] = array::from_fn(|_| HashMap::new());
+
+    // With one word or none left the table carries no signal, so every letter scores zero
+    if words.len() <= 1 {
+        return frequency;
+    }
+
+    for &word in words {
+        let bytes = word.as_bytes();
+        for (i, &c) in bytes.iter().enumerate() {
+            *frequency[i].entry(c).or_insert(0.) += 1.;
+        }
+    }
+
+    for position in frequency.iter_mut() {
+        let total: f64 = position.values().sum();
+        if total == 0. {
+            continue;
+        }
+        for v in position.values_mut() {
+            *v /= total;
+        }
+    }
+
+    frequency
+}
+
+// The characters for green, yellow and gray that results are written with
+pub const DEFAULT_SYMBOLS: [char; 3] = ['g', 'y', 'x'];
+
+pub fn pattern_string(output: &[WordleAnswerColor]) -> String {
+    output
+        .iter()
+        .map(|color| match color {
+            WordleAnswerColor::Green => 'g',
+            WordleAnswerColor::Yellow => 'y',
+            WordleAnswerColor::Gray => 'x',
+        })
+        .collect()
+}
+
+// The emoji summary Wordle shares, e.g. "Wordle 3/6" followed by one row per guess.
+// Unsolved games are scored X/6
+pub fn share_grid<const N: usize>(patterns: &[[WordleAnswerColor; N]], solved: bool) -> String {
+    let score = if solved {
+        patterns.len().to_string()
+    } else {
+        "X".to_owned()
+    };
+
+    let mut grid = format!("Wordle {}/{}\n", score, MAX_GUESSES);
+    for pattern in patterns {
+        grid.push('\n');
+        grid.extend(pattern.iter().map(|color| match color {
+            WordleAnswerColor::Green => '🟩',
+            WordleAnswerColor::Yellow => '🟨',
+            WordleAnswerColor::Gray => '⬛',
+        }));
+    }
+    grid
+}
+
+// Inverse of pattern_string; None unless the input is exactly N of 'g', 'y' and 'x'
+pub fn parse_pattern<const N: usize>(pattern: &str) -> Option<[WordleAnswerColor; N]> {
+    parse_pattern_with(pattern, DEFAULT_SYMBOLS)
+}
+
+// Like parse_pattern, for clones that write results with other characters
+pub fn parse_pattern_with<const N: usize>(
+    pattern: &str,
+    [green, yellow, gray]: [char; 3],
+) -> Option<[WordleAnswerColor; N]> {
+    let colors: Vec<WordleAnswerColor> = pattern
+        .chars()
+        .map(|c| match c {
+            _ if c == green => Some(WordleAnswerColor::Green),
+            _ if c == yellow => Some(WordleAnswerColor::Yellow),
+            _ if c == gray => Some(WordleAnswerColor::Gray),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+
+    colors.try_into().ok()
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct SeenLetterBitFlags(u32);
+
+impl Default for SeenLetterBitFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SeenLetterBitFlags {
+    pub fn new() -> Self {
+        Self(0)
+    }
+
+    // Letters outside 'a'..='z' have no flag, so they are never recorded as seen
+    fn flag_of_char(c: char) -> u32 {
+        debug_assert!(c.is_ascii_lowercase(), "'{}' is not a lowercase letter", c);
+        letter_index(c).map_or(0, |index| 0x1 << index)
+    }
+
+    pub fn set(&mut self, c: char, val: bool) {
+        let flag = Self::flag_of_char(c);
+        if val {
+            self.0 |= flag;
+        } else {
+            self.0 &= !flag;
+        }
+    }
+
+    pub fn set_word(&mut self, w: &str, val: bool) {
+        for c in w.chars() {
+            self.set(c, val);
+        }
+    }
+
+    pub fn get(&self, c: char) -> bool {
+        let flag = Self::flag_of_char(c);
+        self.0 & flag != 0
+    }
+
+    pub fn get_word(&self, w: &str) -> usize {
+        w.chars().filter(|c| self.get(*c)).count()
+    }
+
+    // Forgets every letter, so once most of the alphabet has been tried the seen bias can
+    // tell guesses apart again
+    pub fn decay(&mut self) {
+        self.0 = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures::IGHT;
+
+    // Small enough that the solver runs quickly in debug builds
+    const WORDS: [&str; 10] = [
+        "salet", "crane", "trace", "crate", "caret", "react", "slate", "least", "steal", "tales",
+    ];
+
+    fn word_lists(guesses: &[&'static str], solutions: &[&'static str]) -> WordLists {
+        WordLists {
+            guesses: guesses.to_vec(),
+            solutions: solutions.to_vec(),
+        }
+    }
+
+    fn breakdown(guess: &str, words: &[&str], weights: &ScoringWeights) -> ScoreBreakdown {
+        score_breakdown(
+            guess,
+            words,
+            &HashMap::new(),
+            &letter_frequency::<WORD_LENGTH>(words),
+            &SeenLetterBitFlags::new(),
+            &Constraints::new(),
+            weights,
+        )
+    }
+
+    #[test]
+    fn zero_valid_bias_removes_the_term() {
+        let biased = breakdown("crate", &WORDS, &ScoringWeights::default());
+        let weights = ScoringWeights {
+            valid_bias: 0.,
+            ..ScoringWeights::default()
+        };
+        let unbiased = breakdown("crate", &WORDS, &weights);
+
+        assert_eq!(unbiased.valid_bias, 0.);
+        assert!((biased.total - unbiased.total - biased.valid_bias).abs() < 1e-12);
+        assert!(biased.valid_bias > 0.);
+    }
+    #[test]
+    fn gray_copy_shows_an_exact_count() {
+        // "abide" has one 'e', so of the two in "speed" one is yellow and the other gray
+        let mut constraints = Constraints::new();
+        constraints.update_from_guess("speed", simulate_guess("abide", "speed"));
+
+        assert_eq!(constraints.exact_counts(), [(b'e', 1)]);
+        assert!(constraints.to_string().ends_with("Exactly:   e=1"));
+    }
+
+    #[test]
+    fn letter_frequency_of_no_words_has_no_nans() {
+        for words in [&[][..], &["crate"][..]] {
+            let frequencies = letter_frequency::<WORD_LENGTH>(words);
+            assert!(frequencies.iter().all(|position| position.is_empty()));
+
+            let likelihood = word_likelihood_score("crate", &frequencies);
+            assert_eq!(likelihood, 0.);
+            let score = breakdown("crate", words, &ScoringWeights::default());
+            assert!(!score.total.is_nan());
+        }
+    }
+
+    #[test]
+    fn coverage_counts_distinct_unknown_letters() {
+        let mut constraints = Constraints::new();
+        assert_eq!(coverage_score("salet", &constraints), 5.);
+        // Repeated letters only count once
+        assert_eq!(coverage_score("geese", &constraints), 3.);
+
+        // Every letter of "salet" is known either way now, so only 'r' and 'c' are tested
+        constraints.update_from_guess("salet", parse_pattern("gxyxx").unwrap());
+        assert_eq!(coverage_score("salet", &constraints), 0.);
+        assert_eq!(coverage_score("trace", &constraints), 2.);
+    }
+
+    #[test]
+    fn word_list_skips_comments_and_blank_lines() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/testdata/commented_words.txt"
+        );
+        assert_eq!(load_word_list(path).unwrap(), ["crate", "trace", "salet"]);
+    }
+
+    #[test]
+    fn excluding_letters_narrows_the_candidates() {
+        let mut solver = Solver::new(&word_lists(&WORDS, &WORDS), SolverOptions::default());
+        solver.exclude_letters(b"s");
+        assert_eq!(
+            solver.candidates(),
+            ["crane", "trace", "crate", "caret", "react"]
+        );
+
+        solver.exclude_letters(b"n");
+        assert!(!solver.candidates().contains(&"crane"));
+        assert_eq!(solver.candidates().len(), 4);
+    }
+
+    #[test]
+    fn excluding_a_present_letter_changes_nothing() {
+        let mut constraints = Constraints::new();
+        constraints.update_from_guess("crate", parse_pattern("yxxxx").unwrap());
+        let before = constraints.clone();
+
+        constraints.exclude_letter(b'c');
+        assert_eq!(constraints, before);
+        assert!(constraints.matches("music"));
+    }
+
+    #[test]
+    fn count_feedback_counts_greens_and_yellows() {
+        assert_eq!(count_feedback("crate", "crate"), (5, 0));
+        assert_eq!(count_feedback("crate", "trace"), (3, 2));
+        assert_eq!(count_feedback("crate", "salet"), (0, 3));
+        // Only as many copies as the answer has count
+        assert_eq!(count_feedback("crate", "geese"), (1, 0));
+    }
+
+    #[test]
+    fn count_feedback_keeps_words_with_the_same_counts() {
+        let mut solver = Solver::new(&word_lists(&WORDS, &WORDS), SolverOptions::default());
+        solver.apply_count_feedback("salet", count_feedback("slate", "salet"));
+
+        assert!(solver.candidates().contains(&"slate"));
+        assert!(solver
+            .candidates()
+            .iter()
+            .all(|word| count_feedback(word, "salet") == (1, 4)));
+    }
+
+    // The first `size` bundled solutions, enough to give the solver real choices
+    fn sample_words(size: usize) -> Vec<&'static str> {
+        parse_word_list(include_str!("solution_words.txt")).unwrap()[..size].to_vec()
+    }
+
+    #[test]
+    fn any_lookahead_cap_returns_a_legal_guess() {
+        let words = sample_words(120);
+        for (candidates, buckets) in [(1, 1), (3, 2), (10, 8), (50, 50)] {
+            let options = SolverOptions {
+                lookahead: Some(Lookahead {
+                    candidates,
+                    buckets,
+                }),
+                ..SolverOptions::default()
+            };
+            let mut solver = Solver::new(&word_lists(&words, &words), options);
+            solver.apply_feedback("salet", simulate_guess(words[0], "salet"));
+            assert!(solver.candidates().len() > 2);
+
+            let guess = solver.best_guess();
+            assert!(words.contains(&guess), "{} isn't a guess", guess);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn constraints_survive_a_serde_round_trip() {
+        let mut constraints = Constraints::new();
+        constraints.update_from_guess("speed", simulate_guess("abide", "speed"));
+        constraints.exclude_at(0, b'b');
+        constraints.require_letter(b'i');
+
+        let json = serde_json::to_string(&constraints).unwrap();
+        assert!(json.contains("\"excluded_letters\":[\"s\",\"p\"]"));
+        let restored: Constraints = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, constraints);
+
+        let mut seen = SeenLetterBitFlags::new();
+        seen.set_word("speed", true);
+        let restored: SeenLetterBitFlags =
+            serde_json::from_str(&serde_json::to_string(&seen).unwrap()).unwrap();
+        assert_eq!(restored.get_word("posed"), 4);
+
+        let colors = simulate_guess("abide", "speed");
+        let json = serde_json::to_string(&colors).unwrap();
+        assert_eq!(
+            serde_json::from_str::<[WordleAnswerColor; 5]>(&json).unwrap(),
+            colors
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn constraints_reject_non_letters() {
+        let json = serde_json::to_string(&Constraints::new())
+            .unwrap()
+            .replace("\"excluded_letters\":[]", "\"excluded_letters\":[\"1\"]");
+        assert!(serde_json::from_str::<Constraints>(&json).is_err());
+    }
+
+    #[test]
+    fn ranked_candidates_are_sorted_by_probability() {
+        let words = sample_words(120);
+        let mut solver = Solver::new(&word_lists(&words, &words), SolverOptions::default());
+        solver.apply_feedback("salet", simulate_guess(words[0], "salet"));
+
+        let ranked = solver.ranked_candidates();
+        assert_eq!(ranked.len(), solver.candidates().len());
+        assert!(ranked.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        let total: f64 = ranked.iter().map(|&(_, probability)| probability).sum();
+        assert!((total - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn five_of_a_kind_guesses() {
+        assert_eq!(
+            pattern_string(&simulate_guess::<WORD_LENGTH>("geese", "eeeee")),
+            "xggxg"
+        );
+        assert_eq!(
+            pattern_string(&simulate_guess::<WORD_LENGTH>("crane", "eeeee")),
+            "xxxxg"
+        );
+        assert_eq!(
+            pattern_string(&simulate_guess::<WORD_LENGTH>("music", "eeeee")),
+            "xxxxx"
+        );
+        assert_eq!(count_feedback("geese", "eeeee"), (3, 0));
+        assert_eq!(count_feedback("crane", "eeeee"), (1, 0));
+        assert_eq!(count_feedback("music", "eeeee"), (0, 0));
+
+        // The constraints keep exactly the words that would have given the same pattern
+        let words = ["geese", "crane", "music", "eerie", "elite", "these"];
+        for answer in words {
+            let pattern = simulate_guess(answer, "eeeee");
+            let mut constraints = Constraints::new();
+            constraints.update_from_guess("eeeee", pattern);
+            for word in words {
+                assert_eq!(
+                    constraints.matches(word),
+                    simulate_guess(word, "eeeee") == pattern,
+                    "{} against {}",
+                    word,
+                    answer
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tie_break_picks_the_likeliest_guess_within_epsilon() {
+        let words = sample_words(120);
+        let solver = |epsilon| {
+            let options = SolverOptions {
+                strategy: Strategy::TieBreak { epsilon },
+                ..SolverOptions::default()
+            };
+            let mut solver = Solver::new(&word_lists(&words, &words), options);
+            solver.apply_feedback("salet", simulate_guess(words[0], "salet"));
+            solver
+        };
+
+        // No slack leaves only the most informative guesses
+        let strict = solver(0.);
+        let best = words
+            .iter()
+            .map(|word| guess_entropy(word, strict.candidates()))
+            .max_by(f64::total_cmp)
+            .unwrap();
+        assert_eq!(
+            guess_entropy(strict.best_guess(), strict.candidates()),
+            best
+        );
+
+        // With every guess in reach, the likeliest answer wins
+        let loose = solver(100.);
+        let ranked = loose.ranked_candidates();
+        assert!(ranked[0].1 > ranked[ranked.len() - 1].1);
+        let guess = loose.best_guess();
+        let probability = ranked.iter().find(|&&(word, _)| word == guess).unwrap().1;
+        assert_eq!(probability, ranked[0].1);
+    }
+
+    #[test]
+    fn global_frequency_stays_fixed_across_turns() {
+        let words = sample_words(120);
+        let options = SolverOptions {
+            global_frequency: true,
+            ..SolverOptions::default()
+        };
+        let mut global = Solver::new(&word_lists(&words, &words), options);
+        let mut local = Solver::new(&word_lists(&words, &words), SolverOptions::default());
+        let initial = global.frequencies().clone();
+
+        for solver in [&mut global, &mut local] {
+            solver.apply_feedback("salet", simulate_guess(words[0], "salet"));
+        }
+        assert_eq!(*global.frequencies(), initial);
+        assert_ne!(*local.frequencies(), initial);
+    }
+
+    #[test]
+    fn max_bucket_size_is_the_largest_bucket() {
+        let words = sample_words(200);
+        for guess in ["salet", "crane", "geese", "fuzzy"] {
+            let mut buckets: HashMap<[WordleAnswerColor; 5], usize> = HashMap::new();
+            for word in &words {
+                *buckets.entry(simulate_guess(word, guess)).or_default() += 1;
+            }
+            let largest = buckets.into_values().max().unwrap();
+
+            assert_eq!(max_bucket_size(guess, &words), largest);
+            assert_eq!(guess_entropy_report(guess, &words).max_bucket_size, largest);
+        }
+        assert_eq!(max_bucket_size("salet", &[]), 0);
+    }
+
+    #[test]
+    fn answer_equal_to_the_opener_takes_one_guess() {
+        let result =
+            Solver::new(&word_lists(&WORDS, &WORDS), SolverOptions::default()).solve(OPENER);
+        assert!(result.solved());
+        assert_eq!(result.attempts(), 1);
+        assert_eq!(
+            result.game.turns()[0].pattern,
+            [WordleAnswerColor::Green; 5]
+        );
+    }
+
+    #[test]
+    fn requiring_a_letter_drops_words_without_it() {
+        let mut solver = Solver::new(&word_lists(&WORDS, &WORDS), SolverOptions::default());
+        solver.require_letters(b"n");
+        assert_eq!(solver.candidates(), ["crane"]);
+
+        let mut solver = Solver::new(&word_lists(&WORDS, &WORDS), SolverOptions::default());
+        solver.require_letters(b"sl");
+        assert!(solver
+            .candidates()
+            .iter()
+            .all(|word| word.contains('s') && word.contains('l')));
+        assert_eq!(solver.candidates().len(), 5);
+    }
+
+    #[test]
+    fn one_entropy_per_guess() {
+        let words = sample_words(120);
+        for answer in &words[..5] {
+            let result =
+                Solver::new(&word_lists(&words, &words), SolverOptions::default()).solve(answer);
+            assert_eq!(result.entropies.len(), result.attempts());
+            assert_eq!(result.entropies.len(), result.game.turns().len());
+            assert!(result.entropies.iter().all(|&bits| bits >= 0.));
+        }
+    }
+
+    #[test]
+    fn uppercase_word_lists_are_folded_to_lowercase() {
+        assert_eq!(
+            parse_word_list("CRATE\nTrace\n# SALET\nslate\n").unwrap(),
+            ["crate", "trace", "slate"]
+        );
+    }
+
+    #[test]
+    fn expected_attempts_on_a_small_candidate_set() {
+        let mut solver = Solver::new(&word_lists(&WORDS, &WORDS), SolverOptions::default());
+        solver.apply_feedback("salet", parse_pattern("xyxyy").unwrap());
+        assert_eq!(solver.candidates(), ["trace", "crate"]);
+
+        // Equally likely, so one of them is found first time and the other second
+        assert!((solver.expected_attempts() - 1.5).abs() < 1e-9);
+
+        solver.require_letters(b"c");
+        solver.exclude_at(0, b't');
+        assert_eq!(solver.candidates(), ["crate"]);
+        assert_eq!(solver.expected_attempts(), 1.);
+    }
+
+    #[test]
+    fn words_with_digits_are_rejected_cleanly() {
+        assert_eq!(
+            parse_word_list("crate\ncr4te\n"),
+            Err("line 2: invalid word 'cr4te'".to_owned())
+        );
+        assert_eq!(letter_index('4'), None);
+        assert_eq!(letter_index('a'), Some(0));
+        assert_eq!(letter_index('z'), Some(25));
+    }
+
+    #[test]
+    fn share_grid_of_a_known_game() {
+        let patterns = ["xyxyy", "yggyg", "ggggg"]
+            .map(|pattern| parse_pattern::<WORD_LENGTH>(pattern).unwrap());
+        assert_eq!(
+            share_grid(&patterns, true),
+            "Wordle 3/6\n\n⬛🟨⬛🟨🟨\n🟨🟩🟩🟨🟩\n🟩🟩🟩🟩🟩"
+        );
+        assert!(share_grid(&patterns[..2], false).starts_with("Wordle X/6\n"));
+
+        let result =
+            Solver::new(&word_lists(&WORDS, &WORDS), SolverOptions::default()).solve("crate");
+        assert!(result.game.share_grid().ends_with("🟩🟩🟩🟩🟩"));
+    }
+
+    #[test]
+    fn blend_modes_combine_entropy_and_probability() {
+        assert_eq!(Blend::Additive.apply(2., 0.5, 2.), 3.);
+        assert_eq!(Blend::Multiplicative.apply(2., 0.5, 2.), 4.);
+        // Probability alone is worth nothing when multiplied in
+        assert_eq!(Blend::Additive.apply(0., 0.5, 2.), 1.);
+        assert_eq!(Blend::Multiplicative.apply(0., 0.5, 2.), 0.);
+
+        let probabilities = HashMap::from([("crate", 0.5)]);
+        let frequencies = letter_frequency::<WORD_LENGTH>(&WORDS);
+        for blend in [Blend::Additive, Blend::Multiplicative] {
+            let weights = ScoringWeights {
+                blend,
+                ..ScoringWeights::default()
+            };
+            let score = score_breakdown(
+                "crate",
+                &WORDS,
+                &probabilities,
+                &frequencies,
+                &SeenLetterBitFlags::new(),
+                &Constraints::new(),
+                &weights,
+            );
+            let expected = blend.apply(score.entropy, 0.5, weights.bayesian);
+            assert!((score.entropy + score.bayesian - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn game_records_each_turn() {
+        let mut game = Game::new();
+        assert_eq!(game.attempts(), 0);
+        assert!(!game.solved());
+
+        game.push(Turn {
+            guess: "salet".to_owned(),
+            pattern: parse_pattern("xyxyy").unwrap(),
+            remaining_before: 8,
+            remaining_after: 2,
+        });
+        assert_eq!(game.attempts(), 1);
+        assert!(!game.solved());
+        assert_eq!(game.information(), [2.]);
+        assert!(game.hard_mode_violation("crane").is_some());
+        assert_eq!(game.hard_mode_violation("crate"), None);
+
+        game.push(Turn {
+            guess: "crate".to_owned(),
+            pattern: [WordleAnswerColor::Green; 5],
+            remaining_before: 2,
+            remaining_after: 1,
+        });
+        assert!(game.solved());
+        assert_eq!(game.turns()[1].guess, "crate");
+        assert_eq!(game.information(), [2., 1.]);
+    }
+
+    #[test]
+    fn solve_result_reads_the_game() {
+        let solver = Solver::new(&word_lists(&WORDS, &WORDS), SolverOptions::default());
+        let result = solver.solve("crate");
+
+        assert!(result.solved());
+        assert!(!result.failed());
+        assert_eq!(result.guesses().first(), Some(&"salet"));
+        assert_eq!(result.guesses().last(), Some(&"crate"));
+        assert_eq!(result.attempts(), result.game.turns().len());
+    }
+
+    #[test]
+    fn position_exclusion_removes_matching_words() {
+        let mut solver = Solver::new(&word_lists(&WORDS, &WORDS), SolverOptions::default());
+        solver.exclude_at(0, b's');
+        assert!(solver
+            .candidates()
+            .iter()
+            .all(|word| !word.starts_with('s')));
+        assert_eq!(solver.candidates().len(), 7);
+        // 's' elsewhere is still allowed
+        assert!(solver.candidates().contains(&"least"));
+    }
+
+    #[test]
+    fn filter_mismatch_reports_a_seeded_discrepancy() {
+        let expected = ["crate", "trace"];
+        let (kept, dropped) = filter_mismatch(&["crate", "react"], &expected);
+        assert_eq!(kept, ["react"]);
+        assert_eq!(dropped, ["trace"]);
+
+        let (kept, dropped) = filter_mismatch(&["trace", "crate"], &expected);
+        assert!(kept.is_empty() && dropped.is_empty());
+    }
+
+    #[test]
+    fn other_word_lengths_are_rejected() {
+        let err = parse_word_list("crate\nsalt\n").unwrap_err();
+        assert_eq!(
+            err,
+            "line 2: 'salt' has 4 letters, only 5-letter words are supported"
+        );
+    }
+
+    #[test]
+    fn worst_case_weight_subtracts_the_largest_bucket() {
+        let plain = breakdown("salet", &WORDS, &ScoringWeights::default());
+        let weights = ScoringWeights {
+            worst_case: 0.5,
+            ..ScoringWeights::default()
+        };
+        let cautious = breakdown("salet", &WORDS, &weights);
+
+        let largest = max_bucket_size("salet", &WORDS) as f64;
+        assert!(largest > 1.);
+        assert_eq!(plain.worst_case, 0.);
+        assert_eq!(cautious.worst_case, -0.5 * largest);
+        assert!((plain.total - cautious.total - 0.5 * largest).abs() < 1e-12);
+    }
+
+    #[test]
+    fn hard_mode_uses_the_hard_opener() {
+        let lists = word_lists(&WORDS, &WORDS);
+        let opener = |hard_mode| {
+            let options = SolverOptions {
+                hard_mode,
+                first_guess: Some("crate"),
+                first_guess_hard: Some("trace"),
+                ..SolverOptions::default()
+            };
+            Solver::new(&lists, options).opener()
+        };
+        assert_eq!(opener(false), Some("crate"));
+        assert_eq!(opener(true), Some("trace"));
+
+        let options = SolverOptions {
+            hard_mode: true,
+            ..SolverOptions::default()
+        };
+        assert_eq!(Solver::new(&lists, options).opener(), Some(HARD_OPENER));
+    }
+
+    #[test]
+    fn possible_patterns_are_the_distribution_keys() {
+        let patterns = possible_patterns::<WORD_LENGTH>("salet", &WORDS);
+        assert_eq!(
+            patterns.len(),
+            pattern_distribution::<WORD_LENGTH>("salet", &WORDS).len()
+        );
+        assert!(patterns.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn duplicate_words_are_loaded_once() {
+        let words = parse_word_list("crate\ntrace\ncrate\nCRATE\n").unwrap();
+        assert_eq!(words, ["crate", "trace"]);
+    }
+
+    #[test]
+    fn shared_cache_counts_hits_and_misses() {
+        let words = sample_words(100);
+        let lists = word_lists(&words, &words);
+        let cache = Arc::new(GuessCache::new());
+        let solver = || {
+            let mut solver = Solver::new(&lists, SolverOptions::default());
+            solver.set_cache(cache.clone());
+            solver.apply_feedback("salet", simulate_guess(words[0], "salet"));
+            solver
+        };
+
+        let first = solver().best_guess();
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (0, 1));
+
+        // A second solver with the same history reuses the first one's choice
+        assert_eq!(solver().best_guess(), first);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
+    #[test]
+    fn elimination_reason_names_each_constraint() {
+        let reason = |answer: &str, guesses: &[&str], word: &str| {
+            let lists = word_lists(&WORDS, &WORDS);
+            let mut solver = Solver::new(&lists, SolverOptions::default());
+            for guess in guesses {
+                solver.apply_feedback(guess, simulate_guess(answer, guess));
+            }
+            solver.elimination_reason(word)
+        };
+        let reason_after_crate = |word| reason("crate", &["salet", "trace"], word);
+
+        assert_eq!(reason_after_crate("crate"), None);
+        assert_eq!(
+            reason_after_crate("slate").unwrap(),
+            "has excluded letter 's'"
+        );
+        assert_eq!(
+            reason_after_crate("brute").unwrap(),
+            "wrong letter at position 3: 'u' instead of 'a'"
+        );
+        assert_eq!(
+            reason_after_crate("tract").unwrap(),
+            "'t' can't be at position 1"
+        );
+        assert_eq!(
+            reason_after_crate("craze").unwrap(),
+            "missing required letter 't'"
+        );
+        assert_eq!(
+            reason("erase", &["speed"], "haste").unwrap(),
+            "needs at least 2 'e's"
+        );
+        assert_eq!(
+            reason("abide", &["speed"], "exude").unwrap(),
+            "has more than 1 'e's"
+        );
+        assert_eq!(
+            reason("crate", &[], "zzzzz").unwrap(),
+            "not one of the possible answers"
+        );
+    }
+
+    #[test]
+    fn score_components_sum_to_the_total() {
+        let words = &WORDS[1..];
+        let mut probabilities = HashMap::new();
+        probabilities.insert("crate", 0.3);
+        let mut seen = SeenLetterBitFlags::new();
+        seen.set_word("crane", true);
+        let mut constraints = Constraints::new();
+        constraints.update_from_guess("salet", simulate_guess("trace", "salet"));
+        let weights = ScoringWeights {
+            coverage: 0.2,
+            yellow_placement: 0.3,
+            worst_case: 0.05,
+            ..ScoringWeights::default()
+        };
+
+        let score = score_breakdown(
+            "crate",
+            words,
+            &probabilities,
+            &letter_frequency::<WORD_LENGTH>(words),
+            &seen,
+            &constraints,
+            &weights,
+        );
+        let components = [
+            score.entropy,
+            score.bayesian,
+            score.valid_bias,
+            score.likelihood,
+            score.seen_bias,
+            score.coverage,
+            score.yellow_placement,
+            score.worst_case,
+        ];
+        assert!(components.iter().all(|&component| component != 0.));
+        assert!((components.iter().sum::<f64>() - score.total).abs() < 1e-9);
+    }
+
+    #[test]
+    fn endgame_guesses_a_pair_directly_and_probes_a_larger_set() {
+        let mut guesses = IGHT.to_vec();
+        guesses.push("nerfs");
+
+        // A pair finishes within two guesses by trying one of them
+        let answers = ["fight", "light", "sight"];
+        let lists = word_lists(&guesses, &answers);
+        let mut solver = Solver::new(&lists, SolverOptions::default());
+        solver.apply_feedback("sight", simulate_guess("fight", "sight"));
+        assert_eq!(solver.candidates(), ["fight", "light"]);
+        assert!(solver.candidates().contains(&solver.best_guess()));
+
+        // Eight left with five guesses to go can't be tried one by one
+        let lists = word_lists(&guesses, &IGHT);
+        let mut solver = Solver::new(&lists, SolverOptions::default());
+        solver.apply_feedback("tight", simulate_guess("fight", "tight"));
+        assert_eq!(solver.candidates().len(), 8);
+        assert_eq!(solver.best_guess(), "nerfs");
+    }
+
+    #[test]
+    fn table_cache_round_trips() {
+        let words = sample_words(100);
+        let lists = word_lists(&words, &words);
+        let cache = Arc::new(GuessCache::new());
+        for answer in &words[..3] {
+            let mut solver = Solver::new(&lists, SolverOptions::default());
+            solver.set_cache(cache.clone());
+            solver.apply_feedback(words[0], simulate_guess(answer, words[0]));
+            solver.best_guess();
+        }
+
+        let fingerprint = GuessCache::fingerprint(&lists, &SolverOptions::default());
+        let bytes = cache.encode(&words, fingerprint);
+        let decoded = GuessCache::decode(&bytes, &words, fingerprint).unwrap();
+        assert_eq!(
+            *decoded.entries.lock().unwrap(),
+            *cache.entries.lock().unwrap()
+        );
+        assert!(!decoded.entries.lock().unwrap().is_empty());
+
+        assert!(GuessCache::<WORD_LENGTH>::decode(&bytes, &words, fingerprint + 1).is_err());
+        assert!(
+            GuessCache::<WORD_LENGTH>::decode(&bytes[..bytes.len() - 1], &words, fingerprint)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn each_likelihood_norm_scales_as_documented() {
+        let frequencies = letter_frequency::<WORD_LENGTH>(&WORDS);
+        let raw = word_likelihood_score("crate", &frequencies);
+        let stats = LikelihoodNorm::ZScore.stats(&WORDS, &frequencies);
+
+        assert_eq!(LikelihoodNorm::Sum.apply(raw, 5, &stats), raw);
+        assert_eq!(LikelihoodNorm::Average.apply(raw, 5, &stats), raw / 5.);
+        assert_eq!(
+            LikelihoodNorm::ZScore.apply(raw, 5, &stats),
+            (raw - stats.mean) / stats.deviation
+        );
+        assert_eq!(
+            LikelihoodNorm::Sum.stats(&WORDS, &frequencies),
+            LikelihoodStats::default()
+        );
+
+        // Z-scores over the candidates average out to zero
+        let total: f64 = WORDS
+            .iter()
+            .map(|word| {
+                let raw = word_likelihood_score(word, &frequencies);
+                LikelihoodNorm::ZScore.apply(raw, 5, &stats)
+            })
+            .sum();
+        assert!(total.abs() < 1e-9);
+
+        // With every candidate equally likely there's no spread to measure against
+        let single = letter_frequency::<WORD_LENGTH>(&["crate"]);
+        let flat = LikelihoodNorm::ZScore.stats(&["crate"], &single);
+        assert_eq!(LikelihoodNorm::ZScore.apply(1., 5, &flat), 0.);
+    }
+
+    #[test]
+    fn solver_keeps_zscore_stats_in_step_with_the_candidates() {
+        let lists = word_lists(&WORDS, &WORDS);
+        let options = SolverOptions {
+            weights: ScoringWeights {
+                likelihood_norm: LikelihoodNorm::ZScore,
+                ..ScoringWeights::default()
+            },
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::new(&lists, options.clone());
+        solver.apply_feedback("salet", simulate_guess("crate", "salet"));
+
+        let expected = score_breakdown(
+            "trace",
+            solver.candidates(),
+            &solver.probabilities,
+            solver.frequencies(),
+            &solver.seen,
+            solver.constraints(),
+            &options.weights,
+        );
+        assert_eq!(
+            solver.score_breakdown("trace").likelihood,
+            expected.likelihood
+        );
+    }
+
+    // simulate_guess as it was written over chars, kept to check the byte version against
+    fn simulate_guess_by_chars(correct: &str, guess: &str) -> [WordleAnswerColor; 5] {
+        let mut output = [WordleAnswerColor::Gray; 5];
+        let mut unmatched = [0u8; 26];
+
+        for (i, (c, answer)) in guess.chars().zip(correct.chars()).enumerate() {
+            if c == answer {
+                output[i] = WordleAnswerColor::Green;
+            } else if let Some(index) = letter_index(answer) {
+                unmatched[index] += 1;
+            }
+        }
+
+        for (i, c) in guess.chars().enumerate() {
+            let Some(index) = letter_index(c) else {
+                continue;
+            };
+            let count = &mut unmatched[index];
+            if output[i] != WordleAnswerColor::Green && *count > 0 {
+                *count -= 1;
+                output[i] = WordleAnswerColor::Yellow;
+            }
+        }
+
+        output
+    }
+
+    #[test]
+    fn byte_and_char_simulate_agree() {
+        // Every solution against every other, since the full guess list takes too long unoptimized
+        let words = parse_word_list(include_str!("solution_words.txt")).unwrap();
+        for &correct in &words {
+            for &guess in &words {
+                assert_eq!(
+                    simulate_guess(correct, guess),
+                    simulate_guess_by_chars(correct, guess),
+                    "{} against {}",
+                    guess,
+                    correct
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hard_mode_book_entries_are_legal() {
+        let lists = WordLists::load(None, None).unwrap();
+        let options = SolverOptions {
+            hard_mode: true,
+            ..SolverOptions::default()
+        };
+        let book = OpeningBook::<WORD_LENGTH>::builtin(&lists, &options).unwrap();
+        assert!(book.hard_mode && !book.is_empty());
+        for (pattern, guess) in &book.entries {
+            assert!(hard_mode_legal(guess, book.opener, pattern), "{}", guess);
+        }
+
+        // "salet" got the opener's s green, so a second guess without one is refused
+        let text = "# salet 0\ngxxxx crane\n";
+        assert!(OpeningBook::<WORD_LENGTH>::parse(text, &lists.guesses, true).is_err());
+        assert!(OpeningBook::<WORD_LENGTH>::parse(text, &lists.guesses, false).is_ok());
+    }
+
+    #[test]
+    fn cached_and_uncached_bucket_entropy_agree() {
+        let cache = EntropyCache::default();
+        let uncached = |candidates: &[&str]| {
+            WORDS
+                .iter()
+                .map(|word| guess_entropy(word, candidates))
+                .max_by(f64::total_cmp)
+                .unwrap()
+        };
+
+        for candidates in [&WORDS[..], &WORDS[2..6], &WORDS[..]] {
+            assert_eq!(cache.best_entropy(&WORDS, candidates), uncached(candidates));
+        }
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn yellow_placement_counts_untried_spots_for_yellows() {
+        // a, e and t are yellow at the positions salet tried them
+        let mut constraints = Constraints::new();
+        constraints.update_from_guess("salet", simulate_guess("crate", "salet"));
+
+        assert_eq!(yellow_placement_score("salet", &constraints), 0.);
+        assert_eq!(yellow_placement_score("tales", &constraints), 1.);
+        assert_eq!(yellow_placement_score("crate", &constraints), 3.);
+
+        // A green spot is settled, so trying a yellow there gains nothing
+        constraints.update_from_guess("trace", simulate_guess("crate", "trace"));
+        assert_eq!(yellow_placement_score("etaxx", &constraints), 1.);
+    }
+
+    #[test]
+    fn distinct_outcomes_counts_the_distribution() {
+        let words = sample_words(200);
+        for guess in ["salet", "crate", "zzzzz"] {
+            assert_eq!(
+                distinct_outcomes(guess, &words),
+                pattern_distribution::<WORD_LENGTH>(guess, &words).len()
+            );
+        }
+    }
+
+    #[test]
+    fn empty_pools_are_errors() {
+        let search = |guesses: &[&'static str], candidates: &[&'static str]| {
+            try_find_best_guess(
+                guesses,
+                candidates,
+                &HashMap::new(),
+                &letter_frequency::<WORD_LENGTH>(candidates),
+                &SeenLetterBitFlags::new(),
+                &Constraints::new(),
+                &ScoringWeights::default(),
+            )
+        };
+        assert_eq!(search(&WORDS, &[]), Err(SolverError::NoCandidates));
+        assert_eq!(search(&[], &WORDS), Err(SolverError::NoGuesses));
+        assert!(search(&WORDS, &WORDS).is_ok());
+
+        let lists = word_lists(&WORDS, &WORDS);
+        let mut solver = Solver::new(&lists, SolverOptions::default());
+        solver.apply_feedback("salet", parse_pattern("ggggx").unwrap());
+        assert_eq!(solver.try_best_guess(), Err(SolverError::NoCandidates));
+    }
+
+    #[test]
+    fn lenient_yellows_dont_rule_out_their_position() {
+        // The yellow 't' of "salet" against "crate" only says it isn't fifth in strict Wordle
+        let pattern = simulate_guess("crate", "salet");
+        let mut strict = Constraints::new();
+        strict.update_from_guess("salet", pattern);
+        let mut lenient = Constraints::new();
+        lenient.update_from_lenient_guess("salet", pattern);
+
+        for constraints in [&strict, &lenient] {
+            assert!(constraints.matches("crate"));
+            assert!(!constraints.matches("crane"));
+        }
+        assert!(!strict.matches("react"));
+        assert!(lenient.matches("react"));
+
+        let lists = word_lists(&WORDS, &WORDS);
+        let options = SolverOptions {
+            lenient_yellow: true,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::new(&lists, options);
+        solver.apply_feedback("salet", pattern);
+        assert!(solver.candidates().contains(&"react"));
+    }
+
+    #[test]
+    fn solved_game_gains_all_the_information() {
+        let words = sample_words(300);
+        let lists = word_lists(&words, &words);
+        for answer in [words[0], words[150], words[299]] {
+            let result = Solver::new(&lists, SolverOptions::default()).solve(answer);
+            assert!(result.solved());
+
+            let total: f64 = result.game.information().iter().sum();
+            assert!((total - (words.len() as f64).log2()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn coverage_guess_tries_five_new_letters() {
+        let lists = WordLists::load(None, None).unwrap();
+        let distinct = |word: &str| {
+            let mut letters: Vec<u8> = word.bytes().collect();
+            letters.sort_unstable();
+            letters.dedup();
+            letters
+        };
+
+        let mut seen = SeenLetterBitFlags::new();
+        let constraints = Constraints::new();
+        let first = best_coverage_guess(&lists.guesses, &lists.solutions, &constraints, &seen);
+        assert_eq!(distinct(first).len(), 5);
+
+        seen.set_word(first, true);
+        let second = best_coverage_guess(&lists.guesses, &lists.solutions, &constraints, &seen);
+        assert_eq!(distinct(second).len(), 5);
+        assert!(second.bytes().all(|c| !first.as_bytes().contains(&c)));
+    }
+
+    #[test]
+    fn hard_mode_risk_flags_too_many_lookalikes() {
+        let lists = word_lists(&IGHT, &IGHT);
+        let risk = |hard_mode| {
+            let options = SolverOptions {
+                hard_mode,
+                ..SolverOptions::default()
+            };
+            let mut solver = Solver::new(&lists, options);
+            solver.apply_feedback("tight", simulate_guess("fight", "tight"));
+            solver.hard_mode_risk()
+        };
+
+        // Every legal guess only tells whether it is the answer, so five guesses sort out five
+        // of the eight
+        assert_eq!(risk(true), Some(5));
+        assert_eq!(risk(false), None);
+    }
+
+    #[test]
+    fn step_plays_the_guess_it_already_chose() {
+        let words = sample_words(100);
+        let lists = word_lists(&words, &words);
+        let mut solver = Solver::new(&lists, SolverOptions::default());
+        let cache = solver.enable_cache();
+        let answer = words[42];
+
+        let opener = solver.guess().unwrap();
+        let step = solver.step(simulate_guess(answer, opener)).unwrap();
+        assert_eq!(step.guess, opener);
+        let searches = cache.stats().misses + cache.stats().hits;
+        assert_eq!(searches, 1);
+
+        // Asking again, or stepping, doesn't search a second time for the same turn
+        assert_eq!(solver.guess().ok(), step.next_guess);
+        let next = solver
+            .step(simulate_guess(answer, step.next_guess.unwrap()))
+            .unwrap();
+        assert_eq!(next.guess, step.next_guess.unwrap());
+        let after = cache.stats().misses + cache.stats().hits;
+        assert_eq!(after, searches + usize::from(!next.solved));
+
+        // Outside changes make it choose again
+        solver.exclude_letters(b"q");
+        assert!(solver.pending_guess.is_none());
+    }
+
+    #[test]
+    fn penalty_deprioritizes_a_near_tied_guess() {
+        let words = sample_words(200);
+        let lists = word_lists(&words, &words);
+        let mut solver = Solver::new(&lists, SolverOptions::default());
+        solver.apply_feedback("salet", simulate_guess(words[5], "salet"));
+
+        let [(best, fitness), (runner_up, runner_up_fitness)] = solver.top_guesses(2)[..] else {
+            panic!("expected two guesses");
+        };
+        assert_eq!(solver.best_guess(), best);
+
+        let gap = fitness - runner_up_fitness;
+        solver.set_penalties(HashMap::from([(best, gap + 0.01)]));
+        assert_eq!(solver.best_guess(), runner_up);
+
+        // A penalty smaller than the lead changes nothing
+        solver.set_penalties(HashMap::from([(best, gap / 2.)]));
+        assert_eq!(solver.best_guess(), best);
+    }
+
+    #[test]
+    fn validate_game_finds_the_inconsistent_turn() {
+        let game = |turns: &[(&str, &str)]| -> Vec<(String, [WordleAnswerColor; 5])> {
+            turns
+                .iter()
+                .map(|&(guess, pattern)| (guess.to_owned(), parse_pattern(pattern).unwrap()))
+                .collect()
+        };
+
+        let played = game(&[("salet", "xyxyy"), ("trace", "yggyg"), ("crate", "ggggg")]);
+        assert_eq!(validate_game(&played, &WORDS), Ok(()));
+
+        // trace and crate both fit the first turn, but neither gets this for "crane"
+        let contradicted = game(&[("salet", "xyxyy"), ("crane", "ggggg")]);
+        assert_eq!(
+            validate_game(&contradicted, &WORDS),
+            Err(GameInconsistency::NoAnswer { turn: 2 })
+        );
+
+        // The gray 'e' comes before the yellow one, which Wordle never does
+        let impossible = game(&[("salet", "xyxyy"), ("geese", "xxyxx")]);
+        assert_eq!(
+            validate_game(&impossible, &WORDS),
+            Err(GameInconsistency::ImpossiblePattern { turn: 2 })
+        );
+
+        let after_solved = game(&[("crate", "ggggg"), ("trace", "yggyg")]);
+        assert_eq!(
+            validate_game(&after_solved, &WORDS),
+            Err(GameInconsistency::AfterSolved { turn: 2 })
+        );
+
+        let invalid = game(&[("sal3t", "xxxxx")]);
+        assert_eq!(
+            validate_game(&invalid, &WORDS),
+            Err(GameInconsistency::InvalidGuess {
+                turn: 1,
+                guess: "sal3t".to_owned(),
+                length: 5
+            })
+        );
+        assert_eq!(
+            validate_game(&invalid, &WORDS).unwrap_err().to_string(),
+            "turn 1: 'sal3t' is not a 5 letter word"
+        );
+    }
+
+    #[test]
+    fn guesses_lower_bound_is_ceil_log() {
+        assert_eq!(guesses_lower_bound(0, 150), Some(0));
+        assert_eq!(guesses_lower_bound(1, 150), Some(0));
+        assert_eq!(guesses_lower_bound(4, 4), Some(1));
+        assert_eq!(guesses_lower_bound(5, 4), Some(2));
+        // Exact powers don't round up
+        assert_eq!(guesses_lower_bound(16, 4), Some(2));
+        assert_eq!(guesses_lower_bound(17, 4), Some(3));
+        assert_eq!(guesses_lower_bound(221, 72), Some(2));
+        assert_eq!(guesses_lower_bound(2309, 150), Some(2));
+        assert_eq!(guesses_lower_bound(2309, 2), Some(12));
+        assert_eq!(guesses_lower_bound(3, 1), None);
+        assert_eq!(guesses_lower_bound(3, 0), None);
+    }
+
+    #[test]
+    fn max_outcomes_feeds_the_bound() {
+        let candidates = ["hatch", "latch", "match", "patch", "watch"];
+        // Not a real word, but it colours each candidate's first letter differently
+        let guesses = ["hatch", "latch", "match", "patch", "watch", "wlmpz"];
+        let solver = Solver::new(&word_lists(&guesses, &candidates), SolverOptions::default());
+        let outcomes = solver.max_outcomes();
+        assert_eq!(
+            outcomes,
+            guesses
+                .iter()
+                .map(|word| distinct_outcomes(word, &candidates))
+                .max()
+                .unwrap()
+        );
+        assert_eq!(guesses_lower_bound(candidates.len(), outcomes), Some(1));
+    }
+
+    #[test]
+    fn hard_mode_violation_names_the_broken_rule() {
+        let pattern = parse_pattern::<WORD_LENGTH>("gxyxx").unwrap();
+        assert_eq!(hard_mode_violation("crate", "caret", &pattern), None);
+        assert_eq!(
+            hard_mode_violation("trace", "caret", &pattern),
+            Some(HardModeViolation::MovedGreen {
+                position: 1,
+                letter: 'c'
+            })
+        );
+        assert_eq!(
+            hard_mode_violation("clump", "caret", &pattern),
+            Some(HardModeViolation::MissingLetter {
+                letter: 'r',
+                count: 1
+            })
+        );
+
+        let both_e = parse_pattern::<WORD_LENGTH>("xyxxy").unwrap();
+        assert_eq!(
+            hard_mode_violation("crane", "geese", &both_e),
+            Some(HardModeViolation::MissingLetter {
+                letter: 'e',
+                count: 2
+            })
+        );
+        assert_eq!(
+            HardModeViolation::MissingLetter {
+                letter: 'e',
+                count: 2
+            }
+            .to_string(),
+            "it must contain 2 'e's"
+        );
+        assert_eq!(
+            HardModeViolation::MovedGreen {
+                position: 1,
+                letter: 'c'
+            }
+            .to_string(),
+            "letter 1 must be 'c'"
+        );
+    }
+
+    #[test]
+    fn seen_letters_decay_on_schedule() {
+        let mut seen = SeenLetterBitFlags::new();
+        seen.set_word("salet", true);
+        assert_eq!(seen.get_word("least"), 5);
+        seen.decay();
+        assert!(('a'..='z').all(|c| !seen.get(c)));
+
+        let options = SolverOptions {
+            seen_decay: Some(2),
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::new(&word_lists(&WORDS, &WORDS), options);
+        solver.apply_feedback("salet", simulate_guess("react", "salet"));
+        solver.apply_feedback("crane", simulate_guess("react", "crane"));
+        assert_eq!(solver.seen.get_word("sn"), 2);
+
+        // The third turn starts a new window, so only its own letters count
+        solver.apply_feedback("react", simulate_guess("react", "react"));
+        assert_eq!(solver.seen.get_word("react"), 5);
+        assert_eq!(solver.seen.get_word("sln"), 0);
+    }
+
+    #[test]
+    fn a_letter_excluded_then_required_is_allowed_again() {
+        let mut constraints = Constraints::new();
+        constraints.exclude_letter(b'e');
+        assert!(!constraints.matches("crone"));
+
+        // 'e' comes back yellow in salet
+        constraints.update_from_guess("salet", parse_pattern("xxxyx").unwrap());
+        assert!(!constraints.excluded_letters.contains(&b'e'));
+        assert!(constraints.matches("crone"));
+        assert!(!constraints.matches("crony"));
+
+        let mut constraints = Constraints::new();
+        constraints.update_from_guess("eerie", parse_pattern("xxxxx").unwrap());
+        assert_eq!(constraints.max_counts.get(&b'e'), Some(&0));
+        constraints.require_letter(b'e');
+        assert!(!constraints.excluded_letters.contains(&b'e'));
+        assert_eq!(constraints.max_counts.get(&b'e'), None);
+        assert!(constraints.matches("coped"));
+    }
+
+    #[test]
+    fn ev_guess_goes_for_one_of_two_candidates() {
+        let remaining = ["crate", "trace"];
+        let uniform = HashMap::new();
+        // Guessing one is 1.5 guesses expected, anything else needs at least 2
+        assert_eq!(expected_guesses("crate", &remaining, |_| 0.5), 1.5);
+        assert!(expected_guesses("salet", &remaining, |_| 0.5) >= 2.);
+        assert_eq!(ev_guess(&remaining, &uniform, &WORDS, 4), "crate");
+
+        // The likelier of the two is tried first, even on the last guess
+        let probabilities = HashMap::from([("crate", 0.2), ("trace", 0.8)]);
+        assert_eq!(ev_guess(&remaining, &probabilities, &WORDS, 4), "trace");
+        assert_eq!(ev_guess(&remaining, &probabilities, &WORDS, 1), "trace");
+
+        let options = SolverOptions {
+            strategy: Strategy::ExpectedValue,
+            ..SolverOptions::default()
+        };
+        let mut solver = Solver::new(&word_lists(&WORDS, &remaining), options);
+        solver.apply_feedback("salet", simulate_guess("trace", "salet"));
+        assert_eq!(solver.candidates().len(), 2);
+        assert!(remaining.contains(&solver.best_guess()));
+    }
+
+    #[test]
+    fn letter_conversions_reject_anything_outside_a_to_z() {
+        assert_eq!(byte_to_char(b'a'), Some('a'));
+        assert_eq!(byte_to_char(b'z'), Some('z'));
+        assert_eq!(char_to_byte('q'), Some(b'q'));
+        for c in 'a'..='z' {
+            assert_eq!(char_to_byte(c).and_then(byte_to_char), Some(c));
+        }
+
+        for byte in [b'A', b'Z', b'0', b'`', b'{', b' ', 0xe9] {
+            assert_eq!(byte_to_char(byte), None, "{}", byte);
+        }
+        // 'ā' is U+0101, which a cast to u8 would turn into 0x01 and 'š' (U+0161) into 'a'
+        for c in ['A', 'Z', '1', '`', '{', 'é', 'ā', 'š'] {
+            assert_eq!(char_to_byte(c), None, "{}", c);
+            assert_eq!(letter_index(c), None, "{}", c);
+        }
+    }
+
+    #[test]
+    fn non_letters_never_color_or_count() {
+        assert_eq!(
+            simulate_guess::<WORD_LENGTH>("cr-te", "c-ate"),
+            parse_pattern("gxxgg").unwrap()
+        );
+        assert_eq!(
+            hard_mode_violation(
+                "crane",
+                "cr.te",
+                &parse_pattern::<WORD_LENGTH>("xxyxx").unwrap()
+            ),
+            None
+        );
+        // Only s, a, l and t carry weight, the '?' doesn't land in some letter's slot
+        let guess = best_coverage_guess(
+            &WORDS,
+            &["sal?t"],
+            &Constraints::new(),
+            &SeenLetterBitFlags::new(),
+        );
+        assert!("salt".chars().all(|c| guess.contains(c)), "{}", guess);
+    }
+
+    #[test]
+    fn unlimited_solves_report_every_guess() {
+        // Only the first letter tells these apart, so some take more than six guesses
+        let lists = word_lists(&IGHT, &IGHT);
+        let limited = Solver::new(&lists, SolverOptions::default());
+        let mut unlimited = limited.clone();
+        unlimited.set_unlimited(true);
+
+        let mut past_the_limit = 0;
+        for answer in IGHT {
+            let result = unlimited.clone().solve(answer);
+            assert!(result.solved(), "{}", answer);
+            let guesses = result.guesses();
+            assert_eq!(guesses.last(), Some(&answer));
+            assert_eq!(result.failed(), result.attempts() > MAX_GUESSES);
+
+            let capped = limited.clone().solve(answer);
+            if result.attempts() > MAX_GUESSES {
+                past_the_limit += 1;
+                assert!(!capped.solved() && capped.failed());
+                assert_eq!(capped.attempts(), MAX_GUESSES);
+                assert_eq!(capped.guesses(), guesses[..MAX_GUESSES]);
+            } else {
+                assert_eq!(capped.guesses(), guesses);
+            }
+        }
+        assert!(past_the_limit > 0);
+    }
+
+    #[test]
+    fn common_opener_prefers_a_solution_word_on_a_tie() {
+        // Each guess tells the two answers apart by its 'c', so they all score one bit
+        let lists = word_lists(&["cazzz", "crate", "trace"], &["crate", "trace"]);
+        assert_eq!(guess_entropy("cazzz", &lists.solutions), 1.);
+        assert_eq!(guess_entropy("crate", &lists.solutions), 1.);
+
+        assert_eq!(best_opener(&lists), Some("cazzz"));
+        assert_eq!(common_opener(&lists, 0.), Some("crate"));
+
+        // Without a solution word to fall back on it keeps the best guess
+        let lists = word_lists(&["cazzz", "czzzz"], &["crate", "trace"]);
+        assert_eq!(common_opener(&lists, 1.), Some("cazzz"));
+        assert_eq!(common_opener(&word_lists(&[], &["crate"]), 1.), None);
+    }
+
+    #[test]
+    fn entropy_percentile_on_a_small_state() {
+        let candidates = ["crate", "trace"];
+        // crate and trace split the two for one bit, salet and zzzzz get the same pattern
+        // from both and learn nothing
+        let guesses = ["crate", "trace", "salet", "zzzzz"];
+        assert_eq!(guess_entropy("salet", &candidates), 0.);
+
+        assert_eq!(entropy_percentile("crate", &guesses, &candidates), 1.);
+        assert_eq!(entropy_percentile("trace", &guesses, &candidates), 1.);
+        assert_eq!(entropy_percentile("salet", &guesses, &candidates), 0.5);
+        assert_eq!(entropy_percentile("zzzzz", &guesses, &candidates), 0.5);
+
+        // salet does tell react apart from the other two, so only zzzzz is worse
+        let candidates = ["crate", "trace", "react"];
+        assert_eq!(entropy_percentile("salet", &guesses, &candidates), 0.5);
+        assert_eq!(entropy_percentile("zzzzz", &guesses, &candidates), 0.25);
+        assert_eq!(entropy_percentile("salet", &[], &candidates), 1.);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn a_partial_game_survives_a_save_and_load() {
+        let lists = word_lists(&WORDS, &WORDS);
+        let mut solver = Solver::new(&lists, SolverOptions::default());
+        solver.apply_feedback("crane", simulate_guess("tales", "crane"));
+        // Not part of any turn, so only the saved constraints remember it
+        solver.exclude_at(4, b't');
+        let before = solver.candidates().to_vec();
+        assert_eq!(before, ["steal", "tales"]);
+
+        let json = serde_json::to_string(&solver.saved_game().unwrap()).unwrap();
+        let saved: SavedGame = serde_json::from_str(&json).unwrap();
+        let mut restored = Solver::new(&lists, SolverOptions::default());
+        restored.restore(saved).unwrap();
+
+        assert_eq!(restored.candidates(), before);
+        assert_eq!(restored.constraints(), solver.constraints());
+        assert_eq!(restored.game().turns()[0].guess, "crane");
+        assert_eq!(restored.turn(), 1);
+        assert_eq!(restored.seen.get_word("crane"), 5);
+        assert_eq!(restored.best_guess(), solver.best_guess());
+
+        // A restored game can only go into a new solver
+        let saved = solver.saved_game().unwrap();
+        assert!(restored.restore(saved).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn unrecordable_or_inconsistent_games_are_refused() {
+        let lists = word_lists(&WORDS, &WORDS);
+        let mut solver = Solver::new(&lists, SolverOptions::default());
+        solver.apply_count_feedback("salet", count_feedback("crate", "salet"));
+        assert!(solver.saved_game().is_err());
+
+        let mut solver = Solver::new(&lists, SolverOptions::default());
+        solver.retain_candidates(|word| word.starts_with('c'));
+        assert!(solver.saved_game().is_err());
+
+        let mut solver = Solver::new(&lists, SolverOptions::default());
+        solver.apply_feedback("salet", parse_pattern("xyxyy").unwrap());
+        solver.apply_feedback("crane", parse_pattern("ggggg").unwrap());
+        let saved = solver.saved_game().unwrap();
+        let mut fresh = Solver::new(&lists, SolverOptions::default());
+        assert_eq!(
+            fresh.restore(saved),
+            Err("turn 2: no answer fits every result so far".to_owned())
+        );
+    }
+
+    #[test]
+    fn dictionaries_take_their_length_from_the_first_word() {
+        assert_eq!(parse_dictionary("lane\nLINE\n"), Ok(vec!["lane", "line"]));
+        assert!(parse_dictionary("lane\ncrane\n")
+            .unwrap_err()
+            .contains("only 4-letter words"));
+        assert!(parse_dictionary("# nothing\n").is_err());
+        // The bundled length is still the only one a plain word list takes
+        assert!(parse_word_list("lane\n").is_err());
+    }
+
+    #[test]
+    fn sized_solvers_play_other_lengths() {
+        let words = ["lane", "line", "lone", "cane", "cone", "bone"];
+        let lists = word_lists(&words, &words);
+        let options = SolverOptions {
+            first_guess: Some("lane"),
+            ..SolverOptions::default()
+        };
+
+        for answer in words {
+            let result = Solver::<4>::sized(&lists, options.clone()).solve(answer);
+            assert!(result.solved());
+            assert_eq!(result.guesses().last(), Some(&answer));
+            assert!(result
+                .game
+                .turns()
+                .iter()
+                .all(|turn| turn.pattern.len() == 4));
+        }
+        assert_eq!(pattern_count(4), 81);
+        assert_eq!(
+            distinct_outcomes("lane", &words),
+            possible_patterns::<4>("lane", &words).len()
+        );
+    }
+}
@@ -1,299 +1,882 @@
+use ideal_wordle_player::*;
 use rayon::prelude::*;
+#[cfg(test)]
+mod fixtures;
+#[cfg(feature = "tui")]
+mod tui;
+
 use std::{
-    array,
-    collections::{HashMap, HashSet},
-    io::Write,
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{BufRead, IsTerminal, Write},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
 };
 
-#[derive(Debug, Clone)]
-struct Constraints {
-    known_letters: [Option<u8>; 5],
-    included_letters: [Vec<u8>; 5],
-    excluded_letters: Vec<u8>,
+struct Options {
+    solver: SolverOptions,
+    reporter: Box<dyn Reporter>,
+    practice: bool,
+    answer: Option<String>,
+    guess_words_path: Option<String>,
+    solution_words_path: Option<String>,
+    blacklist_path: Option<String>,
+    // Other variants' word lists to benchmark, each one both the guesses and the answers
+    dictionaries: Vec<String>,
+    sequential: bool,
+    fail_fast: bool,
+    cache: bool,
+    table_cache: Option<String>,
+    dump_trees: Option<String>,
+    repeat_penalty: f64,
+    pattern_stats: bool,
+    remaining_stats: bool,
+    // Print the turns of every word that took at least this many guesses
+    bench_verbose: Option<usize>,
+    unlimited: bool,
+    build_book: Option<String>,
+    dump_freq: Option<String>,
+    no_book: bool,
+    target_average: f64,
+    mastermind_feedback: bool,
+    // Set by --json, so turns can be sent as JSON too
+    #[cfg(feature = "serde")]
+    json_input: bool,
+    solve: Option<String>,
+    daily: bool,
+    tui: bool,
+    // Letters known to be absent before the first guess
+    exclude: Vec<u8>,
+    // Green, yellow and gray in typed results
+    symbols: [char; 3],
+    verbosity: log::LevelFilter,
+    hint_level: HintLevel,
+    difficulty: Difficulty,
+    hard_words: bool,
+    hard_words_path: Option<String>,
+    bench_all: bool,
+    compute_opener: bool,
+    show_eliminated: bool,
+    show_score_breakdown: bool,
+    // No suggestions at all, just the answer once it's known
+    answer_only: bool,
+    replay: Option<String>,
+    state: Option<String>,
+    // Rewritten with the game so far after every turn the assister accepts
+    save_game: Option<String>,
+    // A game written by --save-game to pick up where it was left
+    load_game: Option<String>,
+    threads: Option<usize>,
+    sample: Option<usize>,
+    first_guess: Option<String>,
+    first_guess_hard: Option<String>,
+    // Open with a solution word if one is within this many bits of the best opener
+    common_opener: Option<f64>,
+    opener_stats: Option<String>,
+    margins: Option<String>,
+    two_player: bool,
+    margin_epsilon: f64,
+    share: bool,
+    book_check: bool,
 }
 
-impl Constraints {
-    fn new() -> Constraints {
-        let known_letters = [None; 5];
-        let included_letters = array::from_fn(|_| Vec::new());
-        let excluded_letters = Vec::new();
+// Which answers practice mode picks from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Difficulty {
+    // The third of the solutions made of the most common letters in their most common spots
+    Easy,
+    Normal,
+    // The third made of the least common letters in their least common spots
+    Hard,
+}
 
-        Constraints {
-            known_letters,
-            included_letters,
-            excluded_letters,
-        }
-    }
+// How much the assister reveals after each turn
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HintLevel {
+    // The best guess, how many candidates remain and the guess's entropy
+    Full,
+    // Only the best guess
+    Medium,
+    // Only whether the input was accepted
+    None,
+}
 
-    fn matches(&self, word: &str) -> bool {
-        let word = word.as_bytes();
+const DEFAULT_EPSILON: f64 = 0.05;
+// How close the best two scores have to be for --margins to call it a near tie
+const DEFAULT_MARGIN_EPSILON: f64 = 0.05;
 
-        for included_set in &self.included_letters {
-            if !included_set.is_empty() && !included_set.iter().any(|&c| word.contains(&c)) {
-                return false;
-            }
-        }
+// Settings that can also be given through the environment, e.g. in a container
+const ENV_FLAGS: [(&str, &str); 7] = [
+    ("WORDLEBOT_FIRST_GUESS", "--first-guess"),
+    ("WORDLEBOT_FIRST_GUESS_HARD", "--first-guess-hard"),
+    ("WORDLEBOT_VALID_BIAS", "--valid-bias"),
+    ("WORDLEBOT_COVERAGE_WEIGHT", "--coverage-weight"),
+    ("WORDLEBOT_YELLOW_WEIGHT", "--yellow-weight"),
+    ("WORDLEBOT_WORST_CASE_WEIGHT", "--worst-case-weight"),
+    ("WORDLEBOT_THREADS", "--threads"),
+];
 
-        if self.excluded_letters.iter().any(|&c| word.contains(&c)) {
-            return false;
-        }
+// The flags for whichever of ENV_FLAGS are set. They go in front of the command line, so a flag
+// given there still wins, and a setting given nowhere keeps its default
+fn env_args(var: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    ENV_FLAGS
+        .iter()
+        .filter_map(|&(name, flag)| var(name).map(|value| [flag.to_owned(), value]))
+        .flatten()
+        .collect()
+}
 
-        for (i, c) in word.iter().enumerate() {
-            if let Some(letter) = self.known_letters[i] {
-                if c != &letter {
-                    return false;
-                }
-            } else if self.included_letters[i].contains(&c) {
-                return false;
-            }
+// Command line flags first, then the environment, then the built-in defaults. Each setting from
+// the environment is checked on its own first, so a bad value is reported under the variable it
+// came from rather than the flag it stands in for
+fn resolve_options(
+    var: impl Fn(&str) -> Option<String>,
+    args: impl Iterator<Item = String>,
+) -> Result<Options, String> {
+    for &(name, flag) in &ENV_FLAGS {
+        if let Some(value) = var(name) {
+            parse_args([flag.to_owned(), value].into_iter())
+                .map_err(|err| err.replace(flag, name))?;
         }
-
-        true
     }
+    parse_args(env_args(var).into_iter().chain(args))
+}
 
-    fn update_from_guess(&mut self, guess: &str, output: [WordleAnswerColor; 5]) {
-        let mut seen = HashSet::new();
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        solver: SolverOptions::default(),
+        reporter: Box::new(TtyReporter::new()),
+        practice: false,
+        answer: None,
+        guess_words_path: None,
+        solution_words_path: None,
+        blacklist_path: None,
+        dictionaries: Vec::new(),
+        sequential: false,
+        fail_fast: false,
+        cache: false,
+        table_cache: None,
+        dump_trees: None,
+        repeat_penalty: 0.,
+        pattern_stats: false,
+        remaining_stats: false,
+        bench_verbose: None,
+        unlimited: false,
+        build_book: None,
+        dump_freq: None,
+        no_book: false,
+        // Best known average for "salet" over the embedded solution list
+        target_average: 3.421,
+        mastermind_feedback: false,
+        #[cfg(feature = "serde")]
+        json_input: false,
+        solve: None,
+        daily: false,
+        tui: false,
+        exclude: Vec::new(),
+        symbols: DEFAULT_SYMBOLS,
+        verbosity: log::LevelFilter::Warn,
+        hint_level: HintLevel::Medium,
+        difficulty: Difficulty::Normal,
+        hard_words: false,
+        hard_words_path: None,
+        bench_all: false,
+        compute_opener: false,
+        show_eliminated: false,
+        show_score_breakdown: false,
+        answer_only: false,
+        replay: None,
+        state: None,
+        save_game: None,
+        load_game: None,
+        threads: None,
+        sample: None,
+        first_guess: None,
+        first_guess_hard: None,
+        common_opener: None,
+        opener_stats: None,
+        margins: None,
+        two_player: false,
+        margin_epsilon: DEFAULT_MARGIN_EPSILON,
+        share: false,
+        book_check: false,
+    };
 
-        for (i, c) in guess.chars().enumerate() {
-            match output[i] {
-                WordleAnswerColor::Green => {
-                    self.known_letters[i] = Some(c as u8);
-                    seen.insert(c as u8);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--valid-bias" => options.solver.weights.valid_bias = parse_value(&arg, args.next())?,
+            "--coverage-weight" => {
+                options.solver.weights.coverage = parse_value(&arg, args.next())?
+            }
+            "--yellow-weight" => {
+                options.solver.weights.yellow_placement = parse_value(&arg, args.next())?
+            }
+            "--worst-case-weight" => {
+                options.solver.weights.worst_case = parse_value(&arg, args.next())?
+            }
+            "-v" => options.verbosity = log::LevelFilter::Debug,
+            "-vv" => options.verbosity = log::LevelFilter::Trace,
+            "--quiet" => options.reporter = Box::new(SilentReporter),
+            "--json" => {
+                options.reporter = Box::new(JsonReporter);
+                #[cfg(feature = "serde")]
+                {
+                    options.json_input = true;
+                }
+            }
+            "--practice" => options.practice = true,
+            "--share" => options.share = true,
+            "--book-check" => options.book_check = true,
+            "--sequential" => options.sequential = true,
+            "--fail-fast" => options.fail_fast = true,
+            "--cache" => options.cache = true,
+            "--dump-trees" => options.dump_trees = Some(parse_value(&arg, args.next())?),
+            "--pattern-stats" => options.pattern_stats = true,
+            "--remaining-stats" => options.remaining_stats = true,
+            "--bench-verbose" => options.bench_verbose = Some(parse_value(&arg, args.next())?),
+            "--unlimited" => options.unlimited = true,
+            "--repeat-penalty" => options.repeat_penalty = parse_value(&arg, args.next())?,
+            "--table-cache" => options.table_cache = Some(parse_value(&arg, args.next())?),
+            "--mastermind-feedback" => options.mastermind_feedback = true,
+            "--solve" => options.solve = Some(parse_value(&arg, args.next())?),
+            "--replay" => options.replay = Some(parse_value(&arg, args.next())?),
+            "--threads" => match parse_value(&arg, args.next())? {
+                0 => return Err("--threads must be at least 1".to_owned()),
+                threads => options.threads = Some(threads),
+            },
+            "--sample" => match parse_value(&arg, args.next())? {
+                0 => return Err("--sample must be at least 1".to_owned()),
+                size => options.sample = Some(size),
+            },
+            "--opener-stats" => options.opener_stats = Some(parse_value(&arg, args.next())?),
+            "--two-player" => options.two_player = true,
+            "--margins" => options.margins = Some(parse_value(&arg, args.next())?),
+            "--margin-epsilon" => options.margin_epsilon = parse_value(&arg, args.next())?,
+            "--symbols" => {
+                let symbols: String = parse_value(&arg, args.next())?;
+                let chars: Vec<char> = symbols.chars().collect();
+                match chars[..] {
+                    [green, yellow, gray]
+                        if green != yellow
+                            && yellow != gray
+                            && green != gray
+                            && !chars.iter().any(|c| c.is_whitespace()) =>
+                    {
+                        options.symbols = [green, yellow, gray]
+                    }
+                    _ => {
+                        return Err(
+                            "--symbols takes three different characters for green, yellow and gray (e.g. 'gyx')"
+                                .to_owned(),
+                        )
+                    }
+                }
+            }
+            "--exclude" => {
+                let letters: String = parse_value(&arg, args.next())?;
+                if letters.is_empty() || !letters.bytes().all(|c| c.is_ascii_lowercase()) {
+                    return Err("--exclude takes lowercase letters (a-z)".to_owned());
+                }
+                options.exclude = letters.into_bytes();
+            }
+            "--first-guess" => options.first_guess = Some(parse_value(&arg, args.next())?),
+            "--first-guess-hard" => {
+                options.first_guess_hard = Some(parse_value(&arg, args.next())?)
+            }
+            "--common-opener" => {
+                let margin: f64 = parse_value(&arg, args.next())?;
+                if !(margin >= 0. && margin.is_finite()) {
+                    return Err("--common-opener takes a non-negative number of bits".to_owned());
                 }
-                WordleAnswerColor::Yellow => {
-                    if !self.included_letters[i].contains(&(c as u8)) {
-                        self.included_letters[i].push(c as u8);
+                options.common_opener = Some(margin);
+            }
+            "--state" => options.state = Some(parse_value(&arg, args.next())?),
+            "--save-game" if cfg!(feature = "serde") => {
+                options.save_game = Some(parse_value(&arg, args.next())?)
+            }
+            "--save-game" => return Err("--save-game requires the 'serde' feature".to_owned()),
+            "--load-game" if cfg!(feature = "serde") => {
+                options.load_game = Some(parse_value(&arg, args.next())?)
+            }
+            "--load-game" => return Err("--load-game requires the 'serde' feature".to_owned()),
+            "--daily" if cfg!(feature = "network") => options.daily = true,
+            "--daily" => return Err("--daily requires the 'network' feature".to_owned()),
+            "--tui" if cfg!(feature = "tui") => options.tui = true,
+            "--tui" => return Err("--tui requires the 'tui' feature".to_owned()),
+            "--strategy" => {
+                options.solver.strategy = match args.next().as_deref() {
+                    Some("additive") => Strategy::Additive,
+                    Some("tie-break") => Strategy::TieBreak {
+                        epsilon: DEFAULT_EPSILON,
+                    },
+                    Some("expected-value") => Strategy::ExpectedValue,
+                    _ => {
+                        return Err(
+                            "--strategy must be 'additive', 'tie-break' or 'expected-value'"
+                                .to_owned(),
+                        )
                     }
-                    seen.insert(c as u8);
                 }
-                WordleAnswerColor::Gray => {
-                    if !self.excluded_letters.contains(&(c as u8)) && !seen.contains(&(c as u8)) {
-                        self.excluded_letters.push(c as u8);
+            }
+            "--blend" => {
+                options.solver.weights.blend = match args.next().as_deref() {
+                    Some("additive") => Blend::Additive,
+                    Some("multiplicative") => Blend::Multiplicative,
+                    _ => return Err("--blend must be 'additive' or 'multiplicative'".to_owned()),
+                }
+            }
+            "--opening-strategy" => {
+                options.solver.opening_strategy = match args.next().as_deref() {
+                    Some("search") => OpeningStrategy::Search,
+                    Some("coverage") => OpeningStrategy::Coverage,
+                    _ => return Err("--opening-strategy must be 'search' or 'coverage'".to_owned()),
+                }
+            }
+            "--metric" => {
+                options.solver.weights.metric = match args.next().as_deref() {
+                    Some("entropy") => Metric::Entropy,
+                    Some("buckets") => Metric::Buckets,
+                    _ => return Err("--metric must be 'entropy' or 'buckets'".to_owned()),
+                }
+            }
+            "--likelihood-norm" => {
+                options.solver.weights.likelihood_norm = match args.next().as_deref() {
+                    Some("sum") => LikelihoodNorm::Sum,
+                    Some("average") => LikelihoodNorm::Average,
+                    Some("zscore") => LikelihoodNorm::ZScore,
+                    _ => {
+                        return Err(
+                            "--likelihood-norm must be 'sum', 'average' or 'zscore'".to_owned()
+                        )
                     }
                 }
             }
+            "--epsilon" => {
+                options.solver.strategy = Strategy::TieBreak {
+                    epsilon: parse_value(&arg, args.next())?,
+                }
+            }
+            "--hint-level" => {
+                options.hint_level = match args.next().as_deref() {
+                    Some("full") => HintLevel::Full,
+                    Some("medium") => HintLevel::Medium,
+                    Some("none") => HintLevel::None,
+                    _ => return Err("--hint-level must be 'full', 'medium' or 'none'".to_owned()),
+                }
+            }
+            "--difficulty" => {
+                options.difficulty = match args.next().as_deref() {
+                    Some("easy") => Difficulty::Easy,
+                    Some("normal") => Difficulty::Normal,
+                    Some("hard") => Difficulty::Hard,
+                    _ => return Err("--difficulty must be 'easy', 'normal' or 'hard'".to_owned()),
+                }
+            }
+            "--show-eliminated" => options.show_eliminated = true,
+            "--show-score-breakdown" => options.show_score_breakdown = true,
+            "--answer-only" => {
+                options.answer_only = true;
+                options.hint_level = HintLevel::None;
+            }
+            "--hard-words" => options.hard_words = true,
+            "--hard-words-file" => {
+                options.hard_words = true;
+                options.hard_words_path = Some(parse_value(&arg, args.next())?);
+            }
+            "--bench-all" => options.bench_all = true,
+            "--compute-opener" => options.compute_opener = true,
+            "--build-book" => options.build_book = Some(parse_value(&arg, args.next())?),
+            "--dump-freq" => options.dump_freq = Some(parse_value(&arg, args.next())?),
+            "--no-book" => options.no_book = true,
+            "--global-frequency" => options.solver.global_frequency = true,
+            "--verify-filter" => options.solver.verify_filter = true,
+            "--lenient-yellow" => options.solver.lenient_yellow = true,
+            "--seen-decay" => {
+                let turns: usize = parse_value(&arg, args.next())?;
+                if turns == 0 {
+                    return Err("--seen-decay must be at least 1".to_owned());
+                }
+                options.solver.seen_decay = Some(turns);
+            }
+            "--lookahead" => {
+                options
+                    .solver
+                    .lookahead
+                    .get_or_insert_with(Lookahead::default);
+            }
+            "--lookahead-candidates" => {
+                options
+                    .solver
+                    .lookahead
+                    .get_or_insert_with(Lookahead::default)
+                    .candidates = parse_value(&arg, args.next())?
+            }
+            "--lookahead-buckets" => {
+                options
+                    .solver
+                    .lookahead
+                    .get_or_insert_with(Lookahead::default)
+                    .buckets = parse_value(&arg, args.next())?
+            }
+            "--target-average" => options.target_average = parse_value(&arg, args.next())?,
+            "--answer" => options.answer = Some(parse_value(&arg, args.next())?),
+            "--guess-words" => options.guess_words_path = Some(parse_value(&arg, args.next())?),
+            "--solution-words" => {
+                options.solution_words_path = Some(parse_value(&arg, args.next())?)
+            }
+            "--blacklist" => options.blacklist_path = Some(parse_value(&arg, args.next())?),
+            "--dictionary" => options.dictionaries.push(parse_value(&arg, args.next())?),
+            _ => return Err(format!("Unknown argument '{}'", arg)),
         }
     }
+
+    if options.answer.is_some() && !options.practice {
+        return Err("--answer can only be used with --practice".to_owned());
+    }
+    // Count feedback isn't kept in the game history, so there would be nothing to save
+    if options.save_game.is_some() && options.mastermind_feedback {
+        return Err("--save-game can't be used with --mastermind-feedback".to_owned());
+    }
+    if options.load_game.is_some() && options.state.is_some() {
+        return Err("--load-game and --state can't be used together".to_owned());
+    }
+
+    Ok(options)
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} requires a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for {}", value, flag))
 }
 
-fn simulate_guess(correct: &str, guess: &str) -> [WordleAnswerColor; 5] {
-    let mut output = [WordleAnswerColor::Gray; 5];
+fn main() {
+    let env = |name: &str| std::env::var(name).ok();
+    let mut options = match resolve_options(env, std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(options.verbosity);
+
+    options.reporter.message("Copyright (C) 2025 Sofia Langer-Osuna\nThis program comes with ABSOLUTELY NO WARRANTY\nThis is free software, and you are welcome to redistribute it under certain conditions.\nSee the LICENSE file for more details.\n");
+
+    let mut word_lists = match WordLists::load(
+        options.guess_words_path.as_deref(),
+        options.solution_words_path.as_deref(),
+    ) {
+        Ok(word_lists) => word_lists,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(path) = &options.blacklist_path {
+        let words = match load_word_list(path) {
+            Ok(words) => words,
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        };
+        let (guesses, solutions) = word_lists.blacklist(&words);
+        options.reporter.message(&format!(
+            "Blacklisted {} words: removed {} guesses and {} solutions",
+            words.len(),
+            guesses,
+            solutions
+        ));
+        if word_lists.solutions.is_empty() {
+            eprintln!("The blacklist removed every solution word");
+            std::process::exit(1);
+        }
+    }
 
-    for (i, c) in guess.chars().enumerate() {
-        if correct.chars().nth(i) == Some(c) {
-            output[i] = WordleAnswerColor::Green;
-        } else if correct.contains(c) {
-            output[i] = WordleAnswerColor::Yellow;
+    if let Some(margin) = options.common_opener {
+        if let Some(opener) = common_opener(&word_lists, margin) {
+            let message = if word_lists.solutions.contains(&opener) {
+                format!(
+                    "Opening with '{}', the best solution word within {} bits of the best",
+                    opener, margin
+                )
+            } else {
+                format!(
+                    "No solution word is within {} bits of the best opener, opening with '{}'",
+                    margin, opener
+                )
+            };
+            options.reporter.message(&message);
+            options.first_guess.get_or_insert_with(|| opener.to_owned());
+            options
+                .first_guess_hard
+                .get_or_insert_with(|| opener.to_owned());
         }
     }
 
-    output
-}
+    if let Err(err) = resolve_openers(&word_lists, &mut options) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
 
-fn shannon_entropy(distribution: &HashMap<[WordleAnswerColor; 5], usize>, total: usize) -> f64 {
-    distribution
-        .values()
-        .map(|&count| {
-            let p = count as f64 / total as f64;
-            -p * p.log2()
-        })
-        .sum()
+    if !options.exclude.is_empty()
+        && word_lists
+            .solutions
+            .iter()
+            .all(|word| word.bytes().any(|c| options.exclude.contains(&c)))
+    {
+        eprintln!(
+            "Every solution uses one of the excluded letters '{}'",
+            String::from_utf8_lossy(&options.exclude)
+        );
+        std::process::exit(1);
+    }
+
+    if let Err(err) = with_threads(options.threads, || run(&word_lists, &mut options)) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
 }
 
-fn word_likelihood_score(word: &str, freq_data: &[HashMap<u8, f64>; 5]) -> f64 {
-    word.as_bytes()
-        .iter()
-        .enumerate()
-        .map(|(i, &c)| *freq_data[i].get(&c).unwrap_or(&0.))
-        .sum()
-}
-
-fn find_guess_fitness(
-    guess: &str,
-    words: &[&str],
-    probabilites: &HashMap<&str, f64>,
-    freq_data: &[HashMap<u8, f64>; 5],
-    seen: &SeenLetterBitFlags,
-) -> f64 {
-    let mut distribution = HashMap::new();
-
-    for &word in words {
-        let pattern = simulate_guess(word, guess);
-        *distribution.entry(pattern).or_insert(0) += 1;
-    }
-
-    let entropy = shannon_entropy(&distribution, words.len());
-    let bayesian = *probabilites.get(guess).unwrap_or(&0.0);
-    let valid_bias = if words.contains(&guess) { 1.0 } else { 0.0 };
-    let likelihood = word_likelihood_score(guess, freq_data) as f64;
-    let seen_bias = seen.get_word(guess) as f64;
-
-    entropy + bayesian * 2.7 + valid_bias * 0.1 + likelihood * 0.01 + seen_bias * -0.1
-}
-
-fn find_best_guess(
-    all_words: &[&'static str],
-    remaining_words: &[&'static str],
-    probabilites: &HashMap<&str, f64>,
-    freq_data: &[HashMap<u8, f64>; 5],
-    seen: &SeenLetterBitFlags,
-) -> &'static str {
-    all_words
-        .par_iter()
-        .map(|&word| {
-            (
-                word,
-                find_guess_fitness(word, remaining_words, probabilites, freq_data, seen),
-            )
-        })
-        .max_by_key(|&guess| ordered_float::OrderedFloat(guess.1))
-        .unwrap()
-        .0
+// Runs `work` on a pool of `threads` threads, or on rayon's global pool if unset
+fn with_threads<R: Send>(
+    threads: Option<usize>,
+    work: impl FnOnce() -> R + Send,
+) -> Result<R, String> {
+    match threads {
+        Some(threads) => rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map(|pool| pool.install(work))
+            .map_err(|err| format!("Failed to start {} threads: {}", threads, err)),
+        None => Ok(work()),
+    }
 }
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-enum WordleAnswerColor {
-    Green,
-    Yellow,
-    Gray,
+// An opener missing from a custom guess list is replaced by the best one it does have,
+// worked out once for both modes. The default opener was only tuned for the bundled
+// solutions, so a custom solution list gets the computed one too unless one was given
+fn resolve_openers(word_lists: &WordLists, options: &mut Options) -> Result<(), String> {
+    let custom_solutions = options.solution_words_path.is_some();
+    let mut computed = None;
+    let mut replaced = Vec::new();
+    let mut resolve_opener = |word: &Option<String>, default: &str| {
+        let explicit = word.is_some();
+        let word = word.as_deref().unwrap_or(default).to_lowercase();
+        let guess = word_lists.guesses.iter().find(|&&guess| guess == word);
+        if let (Some(&guess), true) = (guess, explicit || !custom_solutions) {
+            return Ok((word != default).then_some(guess));
+        }
+
+        let best = *computed.get_or_insert_with(|| best_opener(word_lists));
+        let Some(best) = best else {
+            return Err("The guess list is empty".to_owned());
+        };
+        if !replaced.contains(&word) {
+            options.reporter.message(&if guess.is_some() {
+                format!(
+                    "Opening with '{}', the best opener for these solutions",
+                    best
+                )
+            } else {
+                format!(
+                    "First guess '{}' is not in the guess list, opening with '{}' instead",
+                    word, best
+                )
+            });
+            replaced.push(word);
+        }
+        Ok(Some(best))
+    };
+
+    let first_guess = resolve_opener(&options.first_guess, OPENER)?;
+    let first_guess_hard = resolve_opener(&options.first_guess_hard, HARD_OPENER)?;
+    options.solver.first_guess = first_guess;
+    options.solver.first_guess_hard = first_guess_hard;
+    Ok(())
 }
 
-fn main() {
-    println!("Copyright (C) 2025 Sofia Langer-Osuna\nThis program comes with ABSOLUTELY NO WARRANTY\nThis is free software, and you are welcome to redistribute it under certain conditions.\nSee the LICENSE file for more details.\n");
+fn run(word_lists: &WordLists, options: &mut Options) {
+    if options.practice {
+        run_practice(word_lists, options, &mut std::io::stdin().lock());
+        return;
+    }
 
-    print!("Benchmark? (y/n) ");
-    std::io::stdout().flush().unwrap();
-    let mut bench = String::new();
-    std::io::stdin().read_line(&mut bench).unwrap();
-    let bench = bench.trim().to_lowercase() == "y";
+    if let Some(answer) = &options.solve {
+        options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+        run_solve(word_lists, options, &answer.to_lowercase());
+        return;
+    }
 
-    print!("Hard Mode? (y/n) ");
-    std::io::stdout().flush().unwrap();
-    let mut hard_mode = String::new();
-    std::io::stdin().read_line(&mut hard_mode).unwrap();
-    let hard_mode = hard_mode.trim().to_lowercase() == "y";
+    if let Some(path) = &options.replay {
+        let transcript = match load_transcript(path, options.symbols, &word_lists.solutions) {
+            Ok(transcript) => transcript,
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        };
 
-    if bench {
-        benchmark(hard_mode);
-    } else {
-        run_assister(hard_mode);
+        options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+        run_replay(word_lists, options, &transcript);
+        return;
+    }
+
+    #[cfg(feature = "network")]
+    if options.daily {
+        match ideal_wordle_player::daily::fetch_daily_answer() {
+            Ok(answer) => {
+                options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+                run_solve(word_lists, options, &answer);
+            }
+            Err(err) => options.reporter.message(&err),
+        }
+        return;
+    }
+
+    if options.two_player {
+        options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+        run_two_player(word_lists, options, &mut read_secret);
+        return;
+    }
+
+    if let Some(answer) = &options.margins {
+        options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+        run_margins(word_lists, options, &answer.to_lowercase());
+        return;
+    }
+
+    if let Some(word) = &options.opener_stats {
+        opener_stats(word_lists, options.reporter.as_ref(), &word.to_lowercase());
+        return;
+    }
+
+    if let Some(path) = &options.dump_freq {
+        let reporter = options.reporter.as_ref();
+        let frequencies = new_solver(word_lists, options).frequencies().clone();
+        match dump_freq(path, &frequencies) {
+            Ok(()) => reporter.message(&format!("Wrote letter frequencies to '{}'", path)),
+            Err(err) => reporter.message(&format!("Failed to write '{}': {}", path, err)),
+        }
+        return;
     }
-}
 
-fn update_word_probabilities(
-    words: &[&'static str],
-    constraints: &Constraints,
-) -> HashMap<&'static str, f64> {
-    let mut probabilities = HashMap::new();
+    if let Some(path) = &options.build_book {
+        options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+        build_book(word_lists, options, path);
+        return;
+    }
 
-    for &word in words {
-        let mut score = 1.0;
-        let word_bytes = word.as_bytes();
+    if options.compute_opener {
+        compute_opener(word_lists, options.reporter.as_ref());
+        return;
+    }
 
-        for (i, c) in word_bytes.iter().enumerate() {
-            if let Some(known) = constraints.known_letters[i] {
-                if *c == known {
-                    score *= 1.5; // Boost words matching known letters
+    if !options.dictionaries.is_empty() {
+        let mut dictionaries = Vec::new();
+        for path in &options.dictionaries {
+            match load_dictionary(path) {
+                Ok(words) => dictionaries.push((path.clone(), words)),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
                 }
             }
-            if constraints.included_letters.iter().any(|v| v.contains(c)) {
-                score *= 1.2; // Boost words containing useful letters
-            }
-            if constraints.excluded_letters.contains(c) {
-                score *= 0.1; // Penalize words containing eliminated letters
-            }
         }
 
-        probabilities.insert(word, score);
+        options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+        benchmark_dictionaries(&dictionaries, options);
+        return;
     }
 
-    // Normalize scores into probabilities
-    let total: f64 = probabilities.values().sum();
-    probabilities.iter_mut().for_each(|(_, v)| *v /= total);
+    if options.bench_all {
+        let word_lists = every_guess_an_answer(word_lists);
 
-    probabilities
-}
+        options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+        options.reporter.message(&format!(
+            "Benchmarking all {} guess words; this takes much longer than the solution list.",
+            word_lists.solutions.len()
+        ));
+        benchmark(&word_lists, &word_lists.solutions, options);
+        return;
+    }
 
-fn letter_frequency(words: &[&str]) -> [HashMap<u8, f64>; 5] {
-    let mut frequency: [HashMap<u8, f64>; 5] = Default::default();
+    if let Some(size) = options.sample {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64;
+        let targets = sample_words(&word_lists.solutions, size, seed);
 
-    for &word in words {
-        let bytes = word.as_bytes();
-        for (i, &c) in bytes.iter().enumerate() {
-            *frequency[i].entry(c).or_insert(0.) += 1.;
-        }
+        options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+        options.reporter.message(&format!(
+            "Sampled {} of {} solution words",
+            targets.len(),
+            word_lists.solutions.len()
+        ));
+        benchmark(word_lists, &targets, options);
+        return;
     }
 
-    for i in 0..5 {
-        let total: f64 = frequency[i].values().sum();
-        for v in frequency[i].values_mut() {
-            *v /= total;
+    if options.hard_words {
+        match hard_word_targets(options.hard_words_path.as_deref(), &word_lists.solutions) {
+            Ok(targets) => {
+                options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+                benchmark(word_lists, &targets, options);
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
         }
+        return;
     }
 
-    frequency
-}
+    // Dumping trees and pattern stats only make sense over a benchmark, so don't ask
+    // The TUI is only an assister
+    let bench = options.dump_trees.is_some()
+        || options.pattern_stats
+        || options.remaining_stats
+        || options.bench_verbose.is_some()
+        || !options.tui
+            && prompt_yes_no(
+                options.reporter.as_ref(),
+                "Benchmark? (y/n)",
+                &mut std::io::stdin().lock(),
+            );
+
+    options.solver.hard_mode = prompt_hard_mode(options.reporter.as_ref());
+
+    if bench {
+        benchmark(word_lists, &word_lists.solutions, options);
+    } else {
+        // Earlier turns of a game in progress, in the same format --replay reads
+        let seed = match &options.state {
+            Some(path) => match load_transcript(path, options.symbols, &word_lists.solutions) {
+                Ok(seed) => seed,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            },
+            None => Vec::new(),
+        };
+
+        #[cfg(feature = "tui")]
+        if options.tui {
+            run_tui(word_lists, options, &seed);
+            return;
+        }
 
-fn benchmark(hard_mode: bool) {
-    println!("Running Benchmark...");
+        run_assister(word_lists, options, &seed, &mut std::io::stdin().lock());
+    }
+}
 
-    let mut total_iterations = 0;
-    let mut failures = 0;
+#[cfg(feature = "tui")]
+fn run_tui(
+    word_lists: &WordLists,
+    options: &Options,
+    seed: &[(String, [WordleAnswerColor; WORD_LENGTH])],
+) {
+    let mut solver = new_solver(word_lists, options);
+    for (guess, output) in seed {
+        let Some(&guess) = word_lists.guesses.iter().find(|word| **word == guess) else {
+            eprintln!("Guess '{}' is not a valid word.", guess);
+            std::process::exit(1);
+        };
+        solver.apply_feedback(guess, *output);
+    }
 
-    let iterations = 2309;
+    #[cfg(feature = "serde")]
+    if let Some(path) = &options.load_game {
+        if let Err(err) = load_game(word_lists, path, &mut solver) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
 
-    let all_words: Vec<&'static str> = include_str!("guess_words.txt").lines().collect();
-    print!("\n\n");
+    if let Err(err) = tui::run_tui(
+        word_lists,
+        solver,
+        options.solver.hard_mode,
+        options.symbols,
+    ) {
+        eprintln!("Terminal error: {}", err);
+        std::process::exit(1);
+    }
+}
 
-    for iteration in 0..iterations {
-        let mut words: Vec<&'static str> = include_str!("solution_words.txt").lines().collect();
-        let mut freq_data = letter_frequency(&words);
-        let correct = words[iteration];
-        let mut seen = SeenLetterBitFlags::new();
+trait Reporter: Send + Sync {
+    fn progress(&self, done: usize, total: usize);
+    fn message(&self, msg: &str);
 
-        let mut constraints = Constraints::new();
-        let mut probabilities: HashMap<&str, f64> = HashMap::new();
+    // A transient line describing the current progress, only shown by interactive reporters.
+    fn status(&self, _status: &str) {}
 
-        let max_iterations = 6;
-        let mut i = 0;
+    // Input that couldn't be used, or a game that couldn't be saved. Both only come up with
+    // the 'serde' feature
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    fn error(&self, msg: &str) {
+        self.message(msg);
+    }
 
-        loop {
-            i += 1;
+    // A question whose answer is read from stdin right after, so it stays on the same line
+    fn prompt(&self, question: &str) {
+        print!("{} ", question);
+        std::io::stdout().flush().unwrap();
+    }
 
-            let guess = if i == 1 {
-                "salet" // Hard codes the first best guess because there's no point in calculating it again every time
-            } else if words.len() <= 2 || i >= max_iterations {
-                words.iter().max_by_key(|&word| {
-                    ordered_float::OrderedFloat(*probabilities.get(*word).unwrap_or(&0.0))
-                }).unwrap()
-            } else if !hard_mode {
-                find_best_guess(&all_words, &words, &probabilities, &freq_data, &seen)
-            } else {
-                find_best_guess(&words, &words, &probabilities, &freq_data, &seen)
-            };
-            if guess == correct {
-                break;
-            }
+    fn information(&self, game: &Game) {
+        let Some(first) = game.turns().first() else {
+            return;
+        };
+        let bits = game.information();
+        let per_turn: Vec<String> = bits.iter().map(|&bits| format_bits(bits)).collect();
 
-            seen.set_word(guess, true);
+        self.message(&format!("Information per turn: {}", per_turn.join(", ")));
+        self.message(&format!(
+            "Total: {} of the {} needed to narrow {} candidates to one",
+            format_bits(bits.iter().sum()),
+            format_bits((first.remaining_before as f64).log2()),
+            first.remaining_before
+        ));
+    }
+}
 
-            let output = simulate_guess(correct, guess);
-            constraints.update_from_guess(guess, output);
+// Draws the progress bar and the status line under it in place.
+struct TtyReporter {
+    state: Mutex<TtyState>,
+}
 
-            words.retain(|&word| constraints.matches(word));
-            probabilities = update_word_probabilities(&words, &constraints);
-            freq_data = letter_frequency(&words);
+#[derive(Default)]
+struct TtyState {
+    bar_drawn: bool,
+    status: String,
+}
 
-            if i >= max_iterations {
-                failures += 1;
-                break;
-            }
+impl TtyReporter {
+    fn new() -> Self {
+        TtyReporter {
+            state: Mutex::new(TtyState::default()),
         }
+    }
+}
+
+impl Reporter for TtyReporter {
+    fn progress(&self, done: usize, total: usize) {
+        let mut state = self.state.lock().unwrap();
 
-        total_iterations += i;
+        if state.bar_drawn {
+            print!("\x1B[2A");
+        }
 
-        let portion_done = iteration as f32 / iterations as f32;
+        let portion_done = done as f32 / total as f32;
         let progress_bar_length = 50;
         let progress_bar = (portion_done * progress_bar_length as f32).round() as usize;
         let progress_bar_str = "=".repeat(progress_bar)
@@ -302,163 +885,3316 @@ fn benchmark(hard_mode: bool) {
             } else {
                 "".to_string()
             };
-        println!("\x1B[2A\r[{}]", progress_bar_str);
+        println!("\r[{}]\x1B[K", progress_bar_str);
+        println!("\r{}\x1B[K", state.status);
+        std::io::stdout().flush().unwrap();
 
-        println!(
-            "{:.1}% done {:.1}% accuracy {:.3} average attempts",
-            portion_done * 100.,
-            (1. - failures as f32 / (iteration + 1) as f32) * 100.,
-            total_iterations as f32 / (iteration + 1) as f32
-        );
+        state.bar_drawn = true;
+    }
+
+    fn message(&self, msg: &str) {
+        let mut state = self.state.lock().unwrap();
+
+        println!("{}", msg);
         std::io::stdout().flush().unwrap();
+
+        state.bar_drawn = false;
+        state.status.clear();
     }
 
-    println!(
-        "\x1B[1A\r100.0% done {:.1}% accuracy {:.3} average attempts",
-        (1. - failures as f32 / iterations as f32) * 100.,
-        total_iterations as f32 / iterations as f32,
-    );
+    fn status(&self, status: &str) {
+        let mut state = self.state.lock().unwrap();
 
-    if failures > 0 {
-        println!("{} failures", failures);
+        if state.bar_drawn {
+            println!("\x1B[1A\r{}\x1B[K", status);
+            std::io::stdout().flush().unwrap();
+        }
+
+        state.status = status.to_owned();
     }
 }
 
-fn run_assister(hard_mode: bool) {
-    println!("Running Assister...");
-    println!("Enter your guess and the result (e.g. 'salet ggyyy') or 'exit' to quit.");
-    println!("Result format: g = green, y = yellow, x = gray (e.g. 'ggyyx' for 'salet').");
+// Drops progress updates and prints messages as plain lines.
+struct SilentReporter;
 
-    let mut probabilities: HashMap<&str, f64> = HashMap::new();
-    let mut all_words: Vec<&'static str> = include_str!("guess_words.txt").lines().collect();
-    let mut words: Vec<&'static str> = include_str!("solution_words.txt").lines().collect();
-    let mut seen = SeenLetterBitFlags::new();
-    let mut freq_data = letter_frequency(&words);
-    let mut constraints = Constraints::new();
-    let max_iterations = 6;
-    let mut i = 0;
+impl Reporter for SilentReporter {
+    fn progress(&self, _done: usize, _total: usize) {}
 
-    loop {
-        i += 1;
-        let best_guess = if i == 1 {
-            "salet"
-        } else if words.len() <= 2 || i >= max_iterations {
-            words.iter().max_by_key(|&word| {
-                ordered_float::OrderedFloat(*probabilities.get(*word).unwrap_or(&0.0))
-            }).unwrap()
-        } else {
-            find_best_guess(&all_words, &words, &probabilities, &freq_data, &seen)
-        };
+    fn message(&self, msg: &str) {
+        println!("{}", msg);
+    }
+}
 
-        println!("Best guess: {}", best_guess);
+// Emits one JSON object per line for consumption by other programs.
+struct JsonReporter;
 
-        if loop {
-            std::io::stdout().flush().unwrap();
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).unwrap();
+impl Reporter for JsonReporter {
+    fn progress(&self, done: usize, total: usize) {
+        println!(
+            "{{\"type\":\"progress\",\"done\":{},\"total\":{}}}",
+            done, total
+        );
+    }
 
-            let input = input.trim();
+    fn message(&self, msg: &str) {
+        println!("{{\"type\":\"message\",\"text\":\"{}\"}}", json_escape(msg));
+    }
 
-            if input.eq_ignore_ascii_case("exit") {
-                break true;
-            }
+    fn error(&self, msg: &str) {
+        println!("{{\"type\":\"error\",\"text\":\"{}\"}}", json_escape(msg));
+    }
 
-            let parts: Vec<&str> = input.split_whitespace().collect();
-            if parts.len() != 2 {
-                println!("You must enter two words");
-                println!("Invalid input. Please enter your guess and result (e.g. 'salet ggyyx').");
-                continue;
-            }
-            let guess = parts[0];
-            let result = parts[1];
+    fn prompt(&self, question: &str) {
+        println!(
+            "{{\"type\":\"prompt\",\"text\":\"{}\"}}",
+            json_escape(question)
+        );
+    }
 
-            if guess.len() != 5 || result.len() != 5 {
-                println!("Guess and result must be 5 characters long.");
-                println!("Invalid input. Please enter your guess and result (e.g. 'salet ggyyx').");
-                continue;
-            }
+    fn information(&self, game: &Game) {
+        let Some(first) = game.turns().first() else {
+            return;
+        };
+        let bits = game.information();
+        let per_turn: Vec<String> = bits.iter().map(|bits| format!("{:.3}", bits)).collect();
 
-            if result.chars().any(|c| !matches!(c, 'g' | 'y' | 'x')) {
-                println!("Result must only contain 'g', 'y', or 'x'.");
-                println!("Invalid input. Please enter your guess and result (e.g. 'salet ggyyx').");
-                continue;
-            }
+        println!(
+            "{{\"type\":\"information\",\"bits\":[{}],\"total\":{:.3},\"initial_candidates\":{}}}",
+            per_turn.join(","),
+            bits.iter().sum::<f64>(),
+            first.remaining_before
+        );
+    }
+}
 
-            if !all_words.contains(&guess) {
-                println!("Guess '{}' is not a valid word.", guess);
-                println!("Invalid input. Please enter your guess and result (e.g. 'salet ggyyx').");
-                continue;
-            }
+// One turn as {"guess": "salet", "pattern": "ggyyx"}, with the pattern always in g/y/x
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct JsonTurn {
+    guess: String,
+    pattern: String,
+}
 
-            if result == "ggggg" {
-                println!("Congratulations! You've guessed the word '{}'.", guess);
-                break true;
-            }
+#[cfg(feature = "serde")]
+fn parse_json_turn<const N: usize>(line: &str) -> Result<(String, [WordleAnswerColor; N]), String> {
+    let turn: JsonTurn = serde_json::from_str(line).map_err(|err| {
+        format!(
+            "Expected a JSON object like {{\"guess\": \"salet\", \"pattern\": \"ggyyx\"}}: {}",
+            err
+        )
+    })?;
+    let pattern = parse_pattern(&turn.pattern).ok_or_else(|| {
+        format!(
+            "Invalid pattern '{}', expected {} of g, y and x",
+            turn.pattern, N
+        )
+    })?;
+    Ok((turn.guess.to_lowercase(), pattern))
+}
 
-            let output: Vec<WordleAnswerColor> = result
-                .chars()
-                .map(|c| match c {
-                    'g' => WordleAnswerColor::Green,
-                    'y' => WordleAnswerColor::Yellow,
-                    'x' => WordleAnswerColor::Gray,
-                    _ => panic!("Invalid result character: '{}'. Use 'g', 'y', or 'x'.", c),
-                })
-                .collect();
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
 
-            constraints.update_from_guess(guess, output.try_into().unwrap());
+// Internal decisions are logged to stderr so they don't mix with normal output
+struct StderrLogger;
 
-            break false;
-        } {
-            break;
-        }
+static LOGGER: StderrLogger = StderrLogger;
 
-        words.retain(|&word| constraints.matches(word));
-        if hard_mode {
-            all_words.retain(|&word| constraints.matches(word));
-        }
-        if words.is_empty() {
-            println!("No valid words left. Please check your input.");
-            break;
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", log_line(record));
         }
-        probabilities = update_word_probabilities(&words, &constraints);
-        freq_data = letter_frequency(&words);
-        seen.set_word(best_guess, true);
     }
+
+    fn flush(&self) {}
 }
 
-#[derive(Copy, Clone, Debug)]
-struct SeenLetterBitFlags(u32);
+fn log_line(record: &log::Record) -> String {
+    format!("[{}] {}", record.level(), record.args())
+}
 
-impl SeenLetterBitFlags {
-    fn new() -> Self {
-        Self(0)
+fn prompt_yes_no(reporter: &dyn Reporter, question: &str, input: &mut dyn BufRead) -> bool {
+    reporter.prompt(question);
+    let mut answer = String::new();
+    input.read_line(&mut answer).unwrap();
+    answer.trim().to_lowercase() == "y"
+}
+
+fn prompt_hard_mode(reporter: &dyn Reporter) -> bool {
+    prompt_yes_no(reporter, "Hard Mode? (y/n)", &mut std::io::stdin().lock())
+}
+
+// Piped input can't be hidden, so it's just read as a line
+fn read_secret() -> std::io::Result<String> {
+    if std::io::stdin().is_terminal() {
+        rpassword::read_password()
+    } else {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line).map(|_| line)
     }
+}
+
+// One person types a secret word without it being shown, then the solver tries to find it.
+// An empty secret gives up
+fn run_two_player(
+    word_lists: &WordLists,
+    options: &Options,
+    read_secret: &mut dyn FnMut() -> std::io::Result<String>,
+) {
+    let reporter = options.reporter.as_ref();
+
+    let secret = loop {
+        reporter.prompt("Enter the secret word (it won't be shown):");
+
+        let secret = match read_secret() {
+            Ok(secret) if !secret.is_empty() => secret.trim().to_lowercase(),
+            Ok(_) => return,
+            Err(err) => {
+                eprintln!("Failed to read the secret word: {}", err);
+                std::process::exit(1);
+            }
+        };
+
+        match word_lists.solutions.iter().find(|&&word| word == secret) {
+            Some(&secret) => break secret,
+            None => reporter.message("That's not in the solution list, try another word."),
+        }
+    };
 
-    fn flag_of_char(c: char) -> u32 {
-        0x1 << ((c as u8) - 'a' as u8)
+    let result = new_solver(word_lists, options).play(&mut Secret(secret));
+    print_transcript(reporter, &result);
+
+    if result.failed() && result.solved() {
+        reporter.message(&format!(
+            "The bot found '{}' in {} guesses, past the limit of {}",
+            secret,
+            result.attempts(),
+            MAX_GUESSES
+        ));
+    } else if result.solved() {
+        reporter.message(&format!(
+            "The bot found '{}' in {}/{}",
+            secret,
+            result.attempts(),
+            MAX_GUESSES
+        ));
+    } else {
+        reporter.message(&format!("The bot couldn't find '{}'", secret));
+    }
+}
+
+// Solves `answer` and shows, for every turn, how far the best guess's fitness was ahead of the
+// runner-up. Close calls are where a change in the weights is most likely to change the game
+fn run_margins(word_lists: &WordLists, options: &Options, answer: &str) {
+    let reporter = options.reporter.as_ref();
+
+    if !word_lists.solutions.contains(&answer) {
+        reporter.message(&format!("'{}' is not in the solution list.", answer));
+        return;
     }
 
-    fn set(&mut self, c: char, val: bool) {
-        let flag = Self::flag_of_char(c);
-        if val {
-            self.0 |= flag;
+    let mut solver = new_solver(word_lists, options);
+    for turn in 1..=MAX_GUESSES {
+        let guess = solver.best_guess();
+
+        // Scoring every guess is skipped where it wouldn't tell anything
+        let top = if solver.opener().is_some() || solver.candidates().len() == 1 {
+            Vec::new()
         } else {
-            self.0 &= !flag;
+            solver.top_guesses(2)
+        };
+
+        let margin = match top[..] {
+            _ if solver.opener().is_some() => "fixed opener".to_owned(),
+            _ if solver.candidates().len() == 1 => "only candidate".to_owned(),
+            [(best, fitness), (runner_up, runner_up_fitness)] => {
+                let margin = fitness - runner_up_fitness;
+                format!(
+                    "best {} by {} over {}{}{}",
+                    best,
+                    format_score(margin),
+                    runner_up,
+                    if margin < options.margin_epsilon {
+                        ", near tie"
+                    } else {
+                        ""
+                    },
+                    if best != guess {
+                        ", not the guess played"
+                    } else {
+                        ""
+                    }
+                )
+            }
+            _ => "no runner-up".to_owned(),
+        };
+
+        let output = simulate_guess(answer, guess);
+        reporter.message(&format!(
+            "Turn {}: {} {} ({})",
+            turn,
+            guess,
+            pattern_string(&output),
+            margin
+        ));
+
+        if guess == answer {
+            return;
         }
+        solver.apply_feedback(guess, output);
     }
 
-    fn set_word(&mut self, w: &str, val: bool) {
-        for c in w.chars() {
-            self.set(c, val);
-        }
+    reporter.message(&format!("Failed to solve '{}'", answer));
+}
+
+fn run_solve(word_lists: &WordLists, options: &Options, answer: &str) {
+    let reporter = options.reporter.as_ref();
+
+    if !word_lists.solutions.contains(&answer) {
+        reporter.message(&format!("'{}' is not in the solution list.", answer));
+        return;
+    }
+
+    let result = new_solver(word_lists, options).solve(answer);
+    print_transcript(reporter, &result);
+
+    if result.failed() && result.solved() {
+        reporter.message(&format!(
+            "Solved in {} guesses, past the limit of {}",
+            result.attempts(),
+            MAX_GUESSES
+        ));
+    } else if result.solved() {
+        reporter.message(&format!("Solved in {}/{}", result.attempts(), MAX_GUESSES));
+    } else {
+        reporter.message(&format!("Failed to solve '{}'", answer));
+    }
+    reporter.information(&result.game);
+
+    if options.share {
+        reporter.message(&format!("\n{}", result.game.share_grid()));
     }
 
-    fn get(&self, c: char) -> bool {
-        let flag = Self::flag_of_char(c);
-        self.0 & flag != 0
+    if options.book_check {
+        book_check(word_lists, options, answer);
     }
+}
+
+// Plays the answer once using the opening book and once searching every turn, to check that
+// the book agrees with what the solver works out at runtime
+fn book_check(word_lists: &WordLists, options: &Options, answer: &str) {
+    let reporter = options.reporter.as_ref();
+    let Some(book) = OpeningBook::builtin(word_lists, &options.solver) else {
+        reporter.message("No opening book was built for these word lists and options.");
+        return;
+    };
+
+    let book = Arc::new(book);
+    let searched = Solver::new(word_lists, options.solver.clone());
+    let mut booked = searched.clone();
+    booked.set_book(book.clone());
+    let booked = booked.solve(answer);
+    let searched = searched.solve(answer);
+
+    let agreement = |played: &str, expected: &str| {
+        if played == expected {
+            format!("{} agrees with the book", played)
+        } else {
+            format!("{} but the book has {}", played, expected)
+        }
+    };
+    let turns = searched.game.turns();
+    reporter.message(&format!(
+        "Book check: turn 1 {}",
+        agreement(&turns[0].guess, book.opener)
+    ));
+    if let (Some(turn), Some(expected)) = (turns.get(1), book.second_guess(&turns[0].pattern)) {
+        reporter.message(&format!(
+            "Book check: turn 2 {}",
+            agreement(&turn.guess, expected)
+        ));
+    }
+
+    let (booked_guesses, searched_guesses) = (booked.guesses(), searched.guesses());
+    let length = booked_guesses.len().max(searched_guesses.len());
+    match (0..length).find(|&i| booked_guesses.get(i) != searched_guesses.get(i)) {
+        None => reporter.message(&format!(
+            "Book check: with and without the book the solver plays the same {} guesses",
+            length
+        )),
+        Some(i) => reporter.message(&format!(
+            "Book check: turn {} differs, {} with the book and {} without ({} vs {} guesses)",
+            i + 1,
+            booked_guesses.get(i).copied().unwrap_or("nothing"),
+            searched_guesses.get(i).copied().unwrap_or("nothing"),
+            booked.attempts(),
+            searched.attempts()
+        )),
+    }
+}
+
+// Reads a played game as one "guess pattern" line per turn, e.g. "salet xxyxg".
+// Blank lines and lines starting with '#' are skipped. The game has to be possible with one of
+// `answers`
+fn load_transcript(
+    path: &str,
+    symbols: [char; 3],
+    answers: &[&str],
+) -> Result<Vec<(String, [WordleAnswerColor; WORD_LENGTH])>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read transcript '{}': {}", path, err))?;
+
+    let mut transcript = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let turn = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [guess, pattern] => {
+                parse_pattern_with(pattern, symbols).map(|output| (guess.to_lowercase(), output))
+            }
+            _ => None,
+        };
+
+        match turn {
+            Some(turn) => transcript.push(turn),
+            None => {
+                return Err(format!(
+                    "{}: line {}: expected a guess and a result like 'salet xxyxg'",
+                    path,
+                    i + 1
+                ))
+            }
+        }
+    }
+
+    validate_game(&transcript, answers).map_err(|err| format!("{}: {}", path, err))?;
+    Ok(transcript)
+}
+
+// Walks through a played game, comparing each guess to what the solver would have played
+fn run_replay(
+    word_lists: &WordLists,
+    options: &Options,
+    transcript: &[(String, [WordleAnswerColor; WORD_LENGTH])],
+) {
+    let reporter = options.reporter.as_ref();
+    let mut solver = new_solver(word_lists, options);
+    // The share of legal guesses each turn's guess did at least as well as, by entropy
+    let mut grades = Vec::new();
+
+    for (turn, (guess, output)) in transcript.iter().enumerate() {
+        let Some(&guess) = word_lists.guesses.iter().find(|word| **word == guess) else {
+            reporter.message(&format!(
+                "Turn {}: '{}' is not a valid word.",
+                turn + 1,
+                guess
+            ));
+            return;
+        };
+
+        if options.solver.hard_mode {
+            if let Some(violation) = solver.game().hard_mode_violation(guess) {
+                reporter.message(&format!(
+                    "Turn {}: '{}' breaks hard mode, {}.",
+                    turn + 1,
+                    guess,
+                    violation
+                ));
+                return;
+            }
+        }
+
+        let best = solver.best_guess();
+        let played = guess_entropy(guess, solver.candidates());
+        let suggested = guess_entropy(best, solver.candidates());
+        let grade = entropy_percentile(guess, solver.guesses(), solver.candidates());
+        grades.push(grade);
+
+        if best == guess {
+            reporter.message(&format!(
+                "Turn {}: {} ({}) matches the solver, grade {}",
+                turn + 1,
+                guess,
+                format_bits(played),
+                format_percent(grade)
+            ));
+        } else {
+            reporter.message(&format!(
+                "Turn {}: {} ({}), the solver would play {} ({}, {}), grade {}",
+                turn + 1,
+                guess,
+                format_bits(played),
+                best,
+                format_bits(suggested),
+                format_signed(suggested - played),
+                format_percent(grade)
+            ));
+        }
+
+        if output
+            .iter()
+            .all(|&color| color == WordleAnswerColor::Green)
+        {
+            break;
+        }
+
+        solver.apply_feedback(guess, *output);
+        if solver.candidates().is_empty() {
+            reporter.message("No candidates are consistent with this game.");
+            return;
+        }
+    }
+
+    if !grades.is_empty() {
+        reporter.message(&format!(
+            "Overall grade: {} over {} turns",
+            format_percent(grades.iter().sum::<f64>() / grades.len() as f64),
+            grades.len()
+        ));
+    }
+}
+
+fn print_transcript<const N: usize>(reporter: &dyn Reporter, result: &SolveResult<N>) {
+    for (turn, entropy) in result.game.turns().iter().zip(&result.entropies) {
+        reporter.message(&format!(
+            "{} {} ({})",
+            turn.guess,
+            pattern_string(&turn.pattern),
+            format_bits(*entropy)
+        ));
+    }
+}
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+const OPENER_LIMIT: usize = 10;
+
+// Starts from the --exclude letters, and uses the bundled opening book for the second guess
+// unless it was built for other word lists or options
+fn new_solver(word_lists: &WordLists, options: &Options) -> Solver {
+    sized_solver(word_lists, options)
+}
+
+// new_solver for words of another length
+fn sized_solver<const N: usize>(word_lists: &WordLists, options: &Options) -> Solver<N> {
+    let mut solver = Solver::sized(word_lists, options.solver.clone());
+    solver.set_unlimited(options.unlimited);
+    if !options.exclude.is_empty() {
+        solver.exclude_letters(&options.exclude);
+    }
+    if !options.no_book {
+        if let Some(book) = OpeningBook::builtin(word_lists, &options.solver) {
+            solver.set_book(Arc::new(book));
+        }
+    }
+    solver
+}
+
+fn build_book(word_lists: &WordLists, options: &Options, path: &str) {
+    let reporter = options.reporter.as_ref();
+    reporter.message("Building the opening book...");
+
+    let book = OpeningBook::<WORD_LENGTH>::build(word_lists, &options.solver);
+    let fingerprint = GuessCache::fingerprint(word_lists, &options.solver);
+    match std::fs::write(path, book.to_text(fingerprint)) {
+        Ok(()) => reporter.message(&format!(
+            "Wrote {} second guesses after '{}' to '{}'",
+            book.len(),
+            book.opener,
+            path
+        )),
+        Err(err) => reporter.message(&format!("Failed to write '{}': {}", path, err)),
+    }
+}
+
+// One "position,letter,probability" row per letter seen at each position, positions counted
+// from 1 and letters in alphabetical order
+fn dump_freq(path: &str, frequencies: &[HashMap<u8, f64>]) -> std::io::Result<()> {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    writeln!(file, "position,letter,probability")?;
+
+    for (i, position) in frequencies.iter().enumerate() {
+        let mut letters: Vec<(&u8, &f64)> = position.iter().collect();
+        letters.sort_by_key(|&(&c, _)| c);
+        for (&c, probability) in letters {
+            writeln!(file, "{},{},{:.6}", i + 1, c as char, probability)?;
+        }
+    }
+
+    file.flush()
+}
+
+// Scores every guess word by its entropy over the whole solution list
+fn compute_opener(word_lists: &WordLists, reporter: &dyn Reporter) {
+    reporter.message(&format!(
+        "Scoring {} openers against {} solutions...",
+        word_lists.guesses.len(),
+        word_lists.solutions.len()
+    ));
+
+    let total = word_lists.guesses.len();
+    let step = (total / 100).max(1);
+    let done = AtomicUsize::new(0);
+
+    let mut openers: Vec<(&str, f64)> = word_lists
+        .guesses
+        .par_iter()
+        .map(|&word| {
+            let entropy = guess_entropy(word, &word_lists.solutions);
+            let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+            if done.is_multiple_of(step) || done == total {
+                reporter.progress(done, total);
+            }
+            (word, entropy)
+        })
+        .collect();
+    openers.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    for (word, entropy) in openers.iter().take(OPENER_LIMIT) {
+        reporter.message(&format!("{} {}", word, format_bits(*entropy)));
+    }
+
+    if let Some((best, _)) = openers.first() {
+        reporter.message(&format!("Best opener: {}", best));
+    }
+}
+
+// The full outcome distribution of one opener over the solution list, largest buckets first
+fn opener_stats(word_lists: &WordLists, reporter: &dyn Reporter, word: &str) {
+    if !word_lists.guesses.contains(&word) {
+        reporter.message(&format!("Guess '{}' is not a valid word.", word));
+        return;
+    }
+
+    let mut buckets: Vec<([WordleAnswerColor; WORD_LENGTH], usize)> =
+        pattern_distribution(word, &word_lists.solutions)
+            .into_iter()
+            .collect();
+    buckets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    for (pattern, count) in &buckets {
+        reporter.message(&format!("{} {}", pattern_string(pattern), count));
+    }
+
+    let report = guess_entropy_report(word, &word_lists.solutions);
+    reporter.message(&format!(
+        "{}: {} of entropy, {} of {} patterns occur, at most {} of {} solutions left",
+        word,
+        format_bits(report.entropy),
+        report.buckets,
+        pattern_count(WORD_LENGTH),
+        report.max_bucket_size,
+        word_lists.solutions.len()
+    ));
+}
+
+// How a benchmark went, for adding up several of them
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct BenchSummary {
+    words: usize,
+    failures: usize,
+    attempts: usize,
+}
+
+fn benchmark(
+    word_lists: &WordLists,
+    targets: &[&'static str],
+    options: &Options,
+) -> Option<BenchSummary> {
+    benchmark_until::<WORD_LENGTH>(word_lists, targets, options, interrupt_flag())
+}
+
+// Ctrl-C stops handing out new words and reports on the ones already solved. Only one
+// handler can be installed per process, so a second benchmark reuses the first
+fn interrupt_flag() -> &'static AtomicBool {
+    let _ = ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::Relaxed));
+    &INTERRUPTED
+}
+
+// Stops once `interrupted` is set, with the words solved until then. None if no word was
+// solved, or if --fail-fast stopped at a failure
+fn benchmark_until<const N: usize>(
+    word_lists: &WordLists,
+    targets: &[&'static str],
+    options: &Options,
+    interrupted: &AtomicBool,
+) -> Option<BenchSummary> {
+    let reporter = options.reporter.as_ref();
+    reporter.message("Running Benchmark...");
+
+    let iterations = targets.len();
+    let mut solver = sized_solver::<N>(word_lists, options);
+    let fingerprint = GuessCache::fingerprint(word_lists, &options.solver);
+    let cache = match &options.table_cache {
+        Some(path) => {
+            let cache = Arc::new(load_table_cache(path, word_lists, fingerprint, reporter));
+            solver.set_cache(cache.clone());
+            Some(cache)
+        }
+        None => options.cache.then(|| solver.enable_cache()),
+    };
+
+    #[cfg(feature = "profiling")]
+    let calls_before = simulate_guess_calls();
+
+    let done = AtomicUsize::new(0);
+    let total_attempts = AtomicUsize::new(0);
+    let failures = AtomicUsize::new(0);
+
+    let solve = |&correct: &&'static str| {
+        if interrupted.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let result = solver.clone().solve(correct);
+
+        total_attempts.fetch_add(result.attempts(), Ordering::Relaxed);
+        if result.failed() {
+            failures.fetch_add(1, Ordering::Relaxed);
+        }
+        let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+
+        reporter.status(&format!(
+            "{} done {} accuracy {} average attempts",
+            format_percent(done as f64 / iterations as f64),
+            format_percent(1. - failures.load(Ordering::Relaxed) as f64 / done as f64),
+            format_attempts(total_attempts.load(Ordering::Relaxed) as f64 / done as f64)
+        ));
+        reporter.progress(done, iterations);
+
+        Some((correct, result))
+    };
+
+    let results: Vec<(&str, SolveResult<N>)> = if options.fail_fast {
+        // Runs in order so the reported failure is always the first one in the list
+        let mut results = Vec::new();
+        for correct in targets {
+            let Some((correct, result)) = solve(correct) else {
+                break;
+            };
+            if result.failed() {
+                reporter.message(&format!(
+                    "Failed to solve '{}' ({} of {}):",
+                    correct,
+                    results.len() + 1,
+                    iterations
+                ));
+                print_transcript(reporter, &result);
+                return None;
+            }
+            results.push((correct, result));
+        }
+        results
+    } else if options.sequential {
+        targets.iter().filter_map(solve).collect()
+    } else {
+        targets.par_iter().filter_map(solve).collect()
+    };
+
+    let completed = results.len();
+    if completed < iterations {
+        reporter.message(&format!(
+            "Interrupted after {} of {} words",
+            completed, iterations
+        ));
+        if completed == 0 {
+            return None;
+        }
+    }
+
+    let total_attempts: usize = results.iter().map(|(_, result)| result.attempts()).sum();
+    let failures = results.iter().filter(|(_, result)| result.failed()).count();
+
+    reporter.message(&format!(
+        "{} accuracy {} average attempts",
+        format_percent(1. - failures as f64 / completed as f64),
+        format_attempts(total_attempts as f64 / completed as f64),
+    ));
+
+    let (gap, percent_of_target) = target_gap(
+        total_attempts as f64 / completed as f64,
+        options.target_average,
+    );
+    reporter.message(&format!(
+        "{} attempts from the {} target ({} of target)",
+        format_signed(gap),
+        format_attempts(options.target_average),
+        format_percent(percent_of_target)
+    ));
+
+    if failures > 0 {
+        reporter.message(&format!("{} failures", failures));
+    }
+
+    #[cfg(feature = "profiling")]
+    {
+        let calls = simulate_guess_calls() - calls_before;
+        reporter.message(&format!(
+            "{} simulate_guess calls, {:.0} per word",
+            calls,
+            calls as f64 / completed as f64
+        ));
+    }
+
+    if options.unlimited {
+        let over: Vec<String> = results
+            .iter()
+            .filter(|(_, result)| result.solved() && result.failed())
+            .map(|(answer, result)| format!("{} ({})", answer, result.attempts()))
+            .collect();
+        if !over.is_empty() {
+            reporter.message(&format!(
+                "Solved past the limit of {}: {}",
+                MAX_GUESSES,
+                over.join(", ")
+            ));
+        }
+    }
+
+    if let Some(threshold) = options.bench_verbose {
+        let flagged: Vec<&(&str, SolveResult<N>)> = results
+            .iter()
+            .filter(|(_, result)| result.failed() || result.attempts() >= threshold)
+            .collect();
+        for (answer, result) in flagged.iter().take(BENCH_VERBOSE_LIMIT) {
+            let turns: Vec<String> = result
+                .game
+                .turns()
+                .iter()
+                .map(|turn| format!("{} ({} left)", turn.guess, turn.remaining_after))
+                .collect();
+            reporter.message(&format!(
+                "{}{}: {}",
+                answer,
+                if result.failed() { " (failed)" } else { "" },
+                turns.join(", ")
+            ));
+        }
+        if flagged.len() > BENCH_VERBOSE_LIMIT {
+            reporter.message(&format!(
+                "... and {} more",
+                flagged.len() - BENCH_VERBOSE_LIMIT
+            ));
+        }
+    }
+
+    if options.pattern_stats {
+        let stats = pattern_stats(&results);
+        reporter.message(&format!(
+            "Hardest of the {} patterns the opener got:",
+            stats.len()
+        ));
+        for (pattern, games, average) in stats.iter().take(PATTERN_STATS_LIMIT) {
+            reporter.message(&format!(
+                "{} {} average attempts over {} games",
+                pattern_string(pattern),
+                format_attempts(*average),
+                games
+            ));
+        }
+    }
+
+    if options.remaining_stats {
+        reporter.message("Candidates left after each guess:");
+        for (turn, histogram) in remaining_histograms(&results).iter().enumerate() {
+            reporter.message(&format!(
+                "Guess {}: {} games, median {}, p90 {}, max {}",
+                turn + 1,
+                histogram.values().sum::<usize>(),
+                histogram_percentile(histogram, 0.5),
+                histogram_percentile(histogram, 0.9),
+                histogram.keys().next_back().unwrap_or(&0)
+            ));
+        }
+    }
+
+    if let Some(path) = &options.dump_trees {
+        if let Err(err) = dump_trees(path, &results) {
+            reporter.message(&format!("Failed to write trees to '{}': {}", path, err));
+        }
+    }
+
+    if let Some(cache) = &cache {
+        let stats = cache.stats();
+        reporter.message(&format!(
+            "Guess cache: {} hits {} misses, about {:.1}s saved",
+            stats.hits, stats.misses, stats.estimated_seconds_saved
+        ));
+
+        if let Some(path) = &options.table_cache {
+            if let Err(err) = std::fs::write(path, cache.encode(&word_lists.guesses, fingerprint)) {
+                reporter.message(&format!("Failed to write table cache '{}': {}", path, err));
+            }
+        }
+    }
+
+    if options.sample.is_some() {
+        let (low, high) = wilson_interval(completed - failures, completed);
+        let attempts: Vec<f64> = results
+            .iter()
+            .map(|(_, result)| result.attempts() as f64)
+            .collect();
+        let (mean, margin) = mean_interval(&attempts);
+        reporter.message(&format!(
+            "95% confidence: accuracy {} to {}, average attempts {} ± {}",
+            format_percent(low),
+            format_percent(high),
+            format_attempts(mean),
+            format_attempts(margin)
+        ));
+    }
+
+    Some(BenchSummary {
+        words: completed,
+        failures,
+        attempts: total_attempts,
+    })
+}
+
+// The word lengths --dictionary can benchmark, each of which is its own build of the solver
+const DICTIONARY_LENGTHS: std::ops::RangeInclusive<usize> = 4..=8;
+
+// Benchmarks every dictionary over all of its words, then adds the results up by word length.
+// Each one opens with its own best opener unless the one given is in it. The bundled books
+// are only built for the bundled lists, so they're left out
+fn benchmark_dictionaries(dictionaries: &[(String, Vec<&'static str>)], options: &mut Options) {
+    let solver = options.solver.clone();
+    let no_book = std::mem::replace(&mut options.no_book, true);
+    let mut lengths: BTreeMap<usize, BenchSummary> = BTreeMap::new();
+
+    for (path, words) in dictionaries {
+        let word_lists = WordLists {
+            guesses: words.clone(),
+            solutions: words.clone(),
+        };
+        let length = words[0].len();
+        let best = best_opener(&word_lists);
+        let opener =
+            |given: Option<&'static str>| given.filter(|word| words.contains(word)).or(best);
+        options.solver.first_guess = opener(solver.first_guess);
+        options.solver.first_guess_hard = opener(solver.first_guess_hard);
+
+        options.reporter.message(&format!(
+            "{}: {} {}-letter words",
+            path,
+            words.len(),
+            length
+        ));
+        let summary = match length {
+            4 => benchmark_until::<4>(&word_lists, words, options, interrupt_flag()),
+            5 => benchmark_until::<5>(&word_lists, words, options, interrupt_flag()),
+            6 => benchmark_until::<6>(&word_lists, words, options, interrupt_flag()),
+            7 => benchmark_until::<7>(&word_lists, words, options, interrupt_flag()),
+            8 => benchmark_until::<8>(&word_lists, words, options, interrupt_flag()),
+            _ => {
+                options.reporter.message(&format!(
+                    "Skipping '{}': only words of {} to {} letters are supported",
+                    path,
+                    DICTIONARY_LENGTHS.start(),
+                    DICTIONARY_LENGTHS.end()
+                ));
+                None
+            }
+        };
+
+        if let Some(summary) = summary {
+            let total = lengths.entry(length).or_default();
+            total.words += summary.words;
+            total.failures += summary.failures;
+            total.attempts += summary.attempts;
+        }
+    }
+
+    options.solver = solver;
+    options.no_book = no_book;
+
+    for (length, total) in &lengths {
+        options.reporter.message(&format!(
+            "{} letters: {} accuracy {} average attempts over {} words",
+            length,
+            format_percent(1. - total.failures as f64 / total.words as f64),
+            format_attempts(total.attempts as f64 / total.words as f64),
+            total.words
+        ));
+    }
+}
+
+// The bundled hard words or the ones in `path`, all of which have to be solutions
+fn hard_word_targets(path: Option<&str>, solutions: &[&str]) -> Result<Vec<&'static str>, String> {
+    let targets = match path {
+        Some(path) => load_word_list(path)?,
+        None => parse_word_list(include_str!("hard_words.txt"))?,
+    };
+
+    match targets.iter().find(|word| !solutions.contains(word)) {
+        Some(word) => Err(format!("Hard word '{}' is not in the solution list", word)),
+        None => Ok(targets),
+    }
+}
+
+// Every guess word becomes a possible answer, so the solver has to consider all of them
+fn every_guess_an_answer(word_lists: &WordLists) -> WordLists {
+    WordLists {
+        guesses: word_lists.guesses.clone(),
+        solutions: word_lists.guesses.clone(),
+    }
+}
+
+// Picks `size` distinct words with a partial Fisher-Yates shuffle driven by xorshift
+fn sample_words(words: &[&'static str], size: usize, seed: u64) -> Vec<&'static str> {
+    let mut words = words.to_vec();
+    let mut state = seed | 1;
+
+    let size = size.min(words.len());
+    for i in 0..size {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let j = i + (state % (words.len() - i) as u64) as usize;
+        words.swap(i, j);
+    }
+
+    words.truncate(size);
+    words
+}
+
+const Z_95: f64 = 1.96;
+
+// Wilson score interval for a proportion, which stays inside [0, 1] even at 0% or 100%
+fn wilson_interval(successes: usize, trials: usize) -> (f64, f64) {
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = Z_95 * Z_95;
+
+    let center = (p + z2 / (2. * n)) / (1. + z2 / n);
+    let margin = Z_95 * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt() / (1. + z2 / n);
+    (center - margin, center + margin)
+}
+
+// Mean and the half-width of its normal-approximation interval
+fn mean_interval(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.);
+    }
+
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.);
+    (mean, Z_95 * (variance / n).sqrt())
+}
+
+const PATTERN_STATS_LIMIT: usize = 10;
+const BENCH_VERBOSE_LIMIT: usize = 50;
+
+// Games grouped by the pattern their first guess got, as (pattern, games, average attempts),
+// hardest first
+fn pattern_stats<const N: usize>(
+    results: &[(&str, SolveResult<N>)],
+) -> Vec<([WordleAnswerColor; N], usize, f64)> {
+    let mut totals: HashMap<[WordleAnswerColor; N], (usize, usize)> = HashMap::new();
+    for (_, result) in results {
+        let Some(first) = result.game.turns().first() else {
+            continue;
+        };
+        let total = totals.entry(first.pattern).or_default();
+        total.0 += 1;
+        total.1 += result.attempts();
+    }
+
+    let mut stats: Vec<_> = totals
+        .into_iter()
+        .map(|(pattern, (games, attempts))| (pattern, games, attempts as f64 / games as f64))
+        .collect();
+    stats.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+    stats
+}
+
+// For each turn, how many games had each number of candidates left after it. A game only counts
+// towards the turns it got to
+fn remaining_histograms<const N: usize>(
+    results: &[(&str, SolveResult<N>)],
+) -> Vec<BTreeMap<usize, usize>> {
+    let mut histograms: Vec<BTreeMap<usize, usize>> = Vec::new();
+    for (_, result) in results {
+        for (i, turn) in result.game.turns().iter().enumerate() {
+            if histograms.len() <= i {
+                histograms.push(BTreeMap::new());
+            }
+            *histograms[i].entry(turn.remaining_after).or_default() += 1;
+        }
+    }
+    histograms
+}
+
+// The smallest size at least `fraction` of the games are at or below
+fn histogram_percentile(histogram: &BTreeMap<usize, usize>, fraction: f64) -> usize {
+    let games: usize = histogram.values().sum();
+    let mut seen = 0;
+    for (&size, &count) in histogram {
+        seen += count;
+        if seen as f64 >= fraction * games as f64 {
+            return size;
+        }
+    }
+    0
+}
+
+// One JSON object per line with every turn the solver played for each answer
+fn dump_trees<const N: usize>(
+    path: &str,
+    results: &[(&str, SolveResult<N>)],
+) -> std::io::Result<()> {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+    for (answer, result) in results {
+        let turns: Vec<String> = result
+            .game
+            .turns()
+            .iter()
+            .map(|turn| {
+                format!(
+                    "{{\"guess\":\"{}\",\"pattern\":\"{}\",\"remaining_before\":{},\"remaining_after\":{}}}",
+                    json_escape(&turn.guess),
+                    pattern_string(&turn.pattern),
+                    turn.remaining_before,
+                    turn.remaining_after
+                )
+            })
+            .collect();
+
+        writeln!(
+            file,
+            "{{\"answer\":\"{}\",\"solved\":{},\"turns\":[{}]}}",
+            json_escape(answer),
+            result.solved(),
+            turns.join(",")
+        )?;
+    }
+
+    file.flush()
+}
+
+// A missing or stale cache is not an error, the benchmark just starts from an empty one and
+// overwrites it when done
+fn load_table_cache<const N: usize>(
+    path: &str,
+    word_lists: &WordLists,
+    fingerprint: u64,
+    reporter: &dyn Reporter,
+) -> GuessCache<N> {
+    let Ok(bytes) = std::fs::read(path) else {
+        return GuessCache::default();
+    };
+
+    match GuessCache::decode(&bytes, &word_lists.guesses, fingerprint) {
+        Ok(cache) => cache,
+        Err(err) => {
+            reporter.message(&format!("Ignoring table cache '{}': {}", path, err));
+            GuessCache::default()
+        }
+    }
+}
+
+// Returns how far the average is above the target and how close it gets, as a fraction
+fn target_gap(average: f64, target: f64) -> (f64, f64) {
+    (average - target, target / average)
+}
+
+// Every reported number goes through one of these so each kind always has the same precision.
+// Adding 0.0 turns -0.0 into 0.0 so it never prints with a sign
+fn format_bits(bits: f64) -> String {
+    format!("{:.3} bits", bits + 0.)
+}
+
+fn format_attempts(attempts: f64) -> String {
+    format!("{:.3}", attempts + 0.)
+}
+
+// Accuracy and progress, given as a fraction
+fn format_percent(fraction: f64) -> String {
+    format!("{:.1}%", fraction * 100. + 0.)
+}
+
+// Candidate probabilities are often small, so they keep an extra decimal
+fn format_probability(probability: f64) -> String {
+    format!("{:.2}%", probability * 100. + 0.)
+}
+
+// Fitness and its terms, which mix bits with weighted bonuses and so have no unit
+fn format_score(score: f64) -> String {
+    format!("{:.3}", score + 0.)
+}
+
+// A difference, always with its sign so it reads as ahead or behind. Zero shows as +0.000
+fn format_signed(difference: f64) -> String {
+    format!("{:+.3}", difference + 0.)
+}
+
+const CANDIDATES_LIMIT: usize = 30;
+const ELIMINATED_LIMIT: usize = 10;
+const CONTAINS_LIMIT: usize = 20;
+// For commands that wouldn't change anything. They go straight back to the prompt, so the best
+// guess isn't recomputed for the same state
+const ALREADY_KNOWN: &str = "That's already known, nothing changed.";
+
+// Words in `before` that are no longer in `after`, in their original order
+fn eliminated_words(before: &[&'static str], after: &[&'static str]) -> Vec<&'static str> {
+    let after: HashSet<&str> = after.iter().copied().collect();
+    before
+        .iter()
+        .copied()
+        .filter(|word| !after.contains(word))
+        .collect()
+}
+
+fn run_assister<const N: usize>(
+    word_lists: &WordLists,
+    options: &Options,
+    seed: &[(String, [WordleAnswerColor; N])],
+    input: &mut dyn BufRead,
+) {
+    let reporter = options.reporter.as_ref();
+    reporter.message("Running Assister...");
+    // The examples use salet when it's a valid guess and a result as long as the words
+    let sample = match word_lists.guesses.iter().find(|&&word| word == "salet") {
+        Some(word) => word,
+        None => word_lists.guesses.first().copied().unwrap_or_default(),
+    };
+    let example: String = "ggyyx".chars().cycle().take(N).collect();
+    let example = symbol_string(&parse_pattern::<N>(&example).unwrap(), options.symbols);
+    if options.mastermind_feedback {
+        reporter.message(&format!(
+            "Enter your guess, the number of correct letters in the correct position and the number of correct letters in the wrong position (e.g. '{} 1 2') or 'exit' to quit.",
+            sample
+        ));
+    } else {
+        let [green, yellow, gray] = options.symbols;
+        reporter.message(&format!(
+            "Enter your guess and the result (e.g. '{} {}') or 'exit' to quit.",
+            sample, example
+        ));
+        reporter.message(&format!(
+            "Result format: {} = green, {} = yellow, {} = gray (e.g. '{}' for '{}').",
+            green, yellow, gray, example, sample
+        ));
+    }
+    reporter.message("Type 'state' to show what is known about the answer so far.");
+    reporter.message("Type 'exclude <letters>' to rule out letters you know are absent.");
+    reporter.message("Type 'require <letters>' to add letters you know are present.");
+    reporter.message("Type 'not <position> <letter>' to rule a letter out at one position.");
+    #[cfg(feature = "regex")]
+    reporter.message("Type 'filter <regex>' to keep only the candidates matching a pattern.");
+    reporter.message(&format!(
+        "Type 'candidates [count]' or 'rank [count]' to list the remaining words by likelihood, {} by default.",
+        CANDIDATES_LIMIT
+    ));
+    reporter.message("Type 'contains <letters>' to list the candidates using all of them.");
+    reporter.message("Type 'why <word>' to see how much a guess would narrow things down.");
+    reporter.message("Type 'explain <word>' to see why a word was ruled out.");
+    reporter.message("Type 'answer <word>' once you know the answer to finish with a summary.");
+    reporter.message("Type 'expected' to estimate how many more guesses are needed.");
+    reporter.message("Type 'bound' to see the fewest guesses that could narrow it down to one.");
+    reporter.message("Type 'new' to start over on the next puzzle.");
+
+    let mut solver = sized_solver::<N>(word_lists, options);
+
+    for (guess, output) in seed {
+        let Some(&guess) = word_lists.guesses.iter().find(|word| **word == guess) else {
+            reporter.message(&format!("Guess '{}' is not a valid word.", guess));
+            return;
+        };
+        solver.apply_feedback(guess, *output);
+    }
+
+    #[cfg(feature = "serde")]
+    if let Some(path) = &options.load_game {
+        if let Err(err) = load_game(word_lists, path, &mut solver) {
+            reporter.message(&err);
+            return;
+        }
+    }
+
+    if !seed.is_empty() || options.load_game.is_some() {
+        match solver.candidates() {
+            [] => {
+                reporter.message("No valid words left. Please check your input.");
+                return;
+            }
+            [answer] => {
+                reporter.message(&format!("The answer must be '{}'.", answer));
+                return;
+            }
+            candidates => {
+                reporter.message(&format!("{} candidates remain.", candidates.len()));
+            }
+        }
+    }
+
+    let mut previous_candidates = solver.candidates().to_vec();
+    // Best guesses shown for each earlier puzzle of the session and for this one
+    let mut session: Vec<Vec<&'static str>> = Vec::new();
+    let mut suggested: Vec<&'static str> = Vec::new();
+
+    loop {
+        if options.show_eliminated && previous_candidates.len() != solver.candidates().len() {
+            let eliminated = eliminated_words(&previous_candidates, solver.candidates());
+            let sample: Vec<&str> = eliminated.iter().take(ELIMINATED_LIMIT).copied().collect();
+            reporter.message(&format!(
+                "Eliminated {} words: {}{}",
+                eliminated.len(),
+                sample.join(" "),
+                if eliminated.len() > sample.len() {
+                    " ..."
+                } else {
+                    ""
+                }
+            ));
+        }
+        previous_candidates = solver.candidates().to_vec();
+
+        if let Some(capacity) = solver.hard_mode_risk() {
+            reporter.message(&format!(
+                "Warning: hard mode only allows guesses that can sort out {} of the {} candidates in the {} tries left, so you may have to guess.",
+                capacity,
+                solver.candidates().len(),
+                MAX_GUESSES.saturating_sub(solver.game().attempts())
+            ));
+        }
+
+        if options.hint_level != HintLevel::None {
+            let best_guess = match solver.try_best_guess() {
+                Ok(best_guess) => best_guess,
+                Err(err) => {
+                    reporter.message(&format!("Can't suggest a guess: {}.", err));
+                    break;
+                }
+            };
+            reporter.message(&format!("Best guess: {}", best_guess));
+            if !suggested.contains(&best_guess) {
+                suggested.push(best_guess);
+            }
+
+            if options.hint_level == HintLevel::Full {
+                reporter.message(&format!(
+                    "{} candidates remain, {} of entropy",
+                    solver.candidates().len(),
+                    format_bits(guess_entropy(best_guess, solver.candidates()))
+                ));
+            }
+
+            if options.show_score_breakdown {
+                let score = solver.score_breakdown(best_guess);
+                reporter.message(&format!(
+                    "Score {} = entropy {} + bayesian {} + valid {} + likelihood {} + seen {} + coverage {} + yellow {} + worst case {}",
+                    format_score(score.total),
+                    format_score(score.entropy),
+                    format_score(score.bayesian),
+                    format_score(score.valid_bias),
+                    format_score(score.likelihood),
+                    format_score(score.seen_bias),
+                    format_score(score.coverage),
+                    format_score(score.yellow_placement),
+                    format_score(score.worst_case)
+                ));
+            }
+        }
+
+        let finished = loop {
+            std::io::stdout().flush().unwrap();
+            let mut line = String::new();
+            // End of input, e.g. when a whole game was piped in, ends the session like 'exit'
+            if input.read_line(&mut line).unwrap() == 0 {
+                break true;
+            }
+
+            let input = line.trim();
+
+            if input.is_empty() {
+                continue;
+            }
+
+            // A turn sent as {"guess": "salet", "pattern": "ggyyx"} goes through the same
+            // checks as one typed out
+            #[cfg(feature = "serde")]
+            let translated;
+            #[cfg(feature = "serde")]
+            let input = if options.json_input && input.starts_with('{') {
+                match parse_json_turn::<N>(input) {
+                    Ok((guess, pattern)) => {
+                        translated =
+                            format!("{} {}", guess, symbol_string(&pattern, options.symbols));
+                        translated.as_str()
+                    }
+                    Err(err) => {
+                        reporter.error(&err);
+                        continue;
+                    }
+                }
+            } else {
+                input
+            };
+
+            if input.eq_ignore_ascii_case("exit") {
+                break true;
+            }
+
+            if input.eq_ignore_ascii_case("new") {
+                session.push(std::mem::take(&mut suggested));
+                solver = sized_solver::<N>(word_lists, options);
+                if options.repeat_penalty > 0. {
+                    solver.set_penalties(repeat_penalties(&session, options.repeat_penalty));
+                }
+                previous_candidates = solver.candidates().to_vec();
+                reporter.message("Starting a new puzzle.");
+                break false;
+            }
+
+            if input.eq_ignore_ascii_case("state") {
+                reporter.message(&solver.constraints().to_string());
+                continue;
+            }
+
+            if input.eq_ignore_ascii_case("bound") {
+                let candidates = solver.candidates().len();
+                let outcomes = solver.max_outcomes();
+                match guesses_lower_bound(candidates, outcomes) {
+                    _ if candidates <= 1 => reporter.message("It's already down to one candidate"),
+                    Some(guesses) => reporter.message(&format!(
+                        "At least {} more {} to narrow {} candidates to one, with at most {} outcomes per guess",
+                        guesses,
+                        if guesses == 1 { "guess" } else { "guesses" },
+                        candidates,
+                        outcomes
+                    )),
+                    None => reporter.message(&format!(
+                        "No guess left can tell the {} candidates apart",
+                        candidates
+                    )),
+                }
+                continue;
+            }
+
+            if input.eq_ignore_ascii_case("expected") {
+                reporter.message(&format!(
+                    "About {} more guesses expected",
+                    format_attempts(solver.expected_attempts())
+                ));
+                continue;
+            }
+
+            let parts: Vec<&str> = input.split_whitespace().collect();
+
+            if let Some(&command @ ("candidates" | "rank")) = parts.first() {
+                let limit = match parts[1..] {
+                    [] => CANDIDATES_LIMIT,
+                    [count] => match count.parse() {
+                        Ok(count) => count,
+                        Err(_) => {
+                            reporter.message(&format!("Usage: {} [count]", command));
+                            continue;
+                        }
+                    },
+                    _ => {
+                        reporter.message(&format!("Usage: {} [count]", command));
+                        continue;
+                    }
+                };
+
+                // Probabilities are uniform until the first feedback, ranked_candidates covers that
+                let ranked = solver.ranked_candidates();
+                for (word, probability) in ranked.iter().take(limit) {
+                    reporter.message(&format!("{} {}", word, format_probability(*probability)));
+                }
+                if ranked.len() > limit {
+                    reporter.message(&format!("... and {} more", ranked.len() - limit));
+                }
+                continue;
+            }
+
+            if parts.first() == Some(&"contains") {
+                let letters = parts[1..].concat();
+                if letters.is_empty() || !letters.bytes().all(|c| c.is_ascii_lowercase()) {
+                    reporter.message("Usage: contains <letters> (lowercase a-z)");
+                    continue;
+                }
+
+                // Repeating a letter asks for at least that many copies of it
+                let matching: Vec<&str> = solver
+                    .candidates()
+                    .iter()
+                    .copied()
+                    .filter(|word| {
+                        letters.bytes().all(|c| {
+                            word.bytes().filter(|&w| w == c).count()
+                                >= letters.bytes().filter(|&l| l == c).count()
+                        })
+                    })
+                    .collect();
+                let sample: Vec<&str> = matching.iter().take(CONTAINS_LIMIT).copied().collect();
+                reporter.message(&format!(
+                    "{} of {} candidates contain '{}': {}{}",
+                    matching.len(),
+                    solver.candidates().len(),
+                    letters,
+                    sample.join(" "),
+                    if matching.len() > sample.len() {
+                        " ..."
+                    } else {
+                        ""
+                    }
+                ));
+                continue;
+            }
+
+            if parts.first() == Some(&"answer") {
+                let Some(&word) = parts
+                    .get(1)
+                    .filter(|word| solver.candidates().contains(word))
+                else {
+                    reporter.message("Usage: answer <word> (one of the remaining candidates)");
+                    continue;
+                };
+
+                solver.apply_feedback(word, [WordleAnswerColor::Green; N]);
+                let game = solver.game();
+                reporter.message(&format!(
+                    "Solved in {}/{} with '{}'.",
+                    game.attempts(),
+                    MAX_GUESSES,
+                    word
+                ));
+                reporter.message(&format!("\n{}", game.share_grid()));
+                break true;
+            }
+
+            if parts.first() == Some(&"explain") {
+                let Some(&word) = parts.get(1) else {
+                    reporter.message("Usage: explain <word>");
+                    continue;
+                };
+
+                match solver.elimination_reason(word) {
+                    Some(reason) => reporter.message(&format!("'{}' {}", word, reason)),
+                    None => reporter.message(&format!("'{}' is still a candidate", word)),
+                }
+                continue;
+            }
+
+            if parts.first() == Some(&"why") {
+                let Some(&word) = parts
+                    .get(1)
+                    .filter(|word| word_lists.guesses.contains(word))
+                else {
+                    reporter.message("Usage: why <word> (a valid guess)");
+                    continue;
+                };
+
+                let report = guess_entropy_report(word, solver.candidates());
+                reporter.message(&format!(
+                    "{}: {} of entropy, {} possible outcomes, at most {} of {} candidates left",
+                    word,
+                    format_bits(report.entropy),
+                    report.buckets,
+                    report.max_bucket_size,
+                    solver.candidates().len()
+                ));
+                continue;
+            }
+
+            #[cfg(feature = "regex")]
+            if parts.first() == Some(&"filter") {
+                let pattern = input["filter".len()..].trim();
+                let regex = match regex::Regex::new(pattern) {
+                    Ok(regex) if !pattern.is_empty() => regex,
+                    Ok(_) => {
+                        reporter.message("Usage: filter <regex>");
+                        continue;
+                    }
+                    Err(err) => {
+                        reporter.message(&format!("Invalid regex: {}", err));
+                        continue;
+                    }
+                };
+
+                if !solver.candidates().iter().any(|word| regex.is_match(word)) {
+                    reporter.message(&format!("No candidates match '{}', ignoring it.", pattern));
+                    continue;
+                }
+
+                if solver.candidates().iter().all(|word| regex.is_match(word)) {
+                    reporter.message(ALREADY_KNOWN);
+                    continue;
+                }
+
+                solver.retain_candidates(|word| regex.is_match(word));
+                reporter.message(&format!(
+                    "Filtered by '{}'. {} candidates remain.",
+                    pattern,
+                    solver.candidates().len()
+                ));
+                break false;
+            }
+
+            if let Some(&command @ ("exclude" | "require")) = parts.first() {
+                let letters = parts[1..].concat();
+                if letters.is_empty() || !letters.bytes().all(|c| c.is_ascii_lowercase()) {
+                    reporter.message(&format!("Usage: {} <letters> (lowercase a-z)", command));
+                    continue;
+                }
+
+                let present = letters
+                    .bytes()
+                    .find(|&c| solver.constraints().is_present(c));
+                if let (Some(c), "exclude") = (present, command) {
+                    reporter.message(&format!(
+                        "'{}' is known to be in the answer, so it can't be excluded.",
+                        c as char
+                    ));
+                    continue;
+                }
+
+                let mut updated = solver.constraints().clone();
+                for c in letters.bytes() {
+                    if command == "exclude" {
+                        updated.exclude_letter(c);
+                    } else {
+                        updated.require_letter(c);
+                    }
+                }
+                if updated == *solver.constraints() {
+                    reporter.message(ALREADY_KNOWN);
+                    continue;
+                }
+
+                if !solver.candidates().iter().any(|word| updated.matches(word)) {
+                    reporter.message(&format!(
+                        "No candidates would be left after '{} {}', ignoring it.",
+                        command, letters
+                    ));
+                    continue;
+                }
+
+                if command == "exclude" {
+                    solver.exclude_letters(letters.as_bytes());
+                    reporter.message(&format!(
+                        "Excluded '{}'. {} candidates remain.",
+                        letters,
+                        solver.candidates().len()
+                    ));
+                } else {
+                    solver.require_letters(letters.as_bytes());
+                    reporter.message(&format!(
+                        "Required '{}'. {} candidates remain.",
+                        letters,
+                        solver.candidates().len()
+                    ));
+                }
+                break false;
+            }
+
+            if parts.first() == Some(&"not") {
+                let exclusion = match parts.as_slice() {
+                    [_, position, letter] if letter.len() == 1 => position
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|position| (1..=N).contains(position))
+                        .zip(letter.chars().next().and_then(char_to_byte)),
+                    _ => None,
+                };
+
+                let Some((position, letter)) = exclusion else {
+                    reporter.message(&format!(
+                        "Usage: not <position 1-{}> <letter> (e.g. 'not 3 e')",
+                        N
+                    ));
+                    continue;
+                };
+
+                let mut updated = solver.constraints().clone();
+                updated.exclude_at(position - 1, letter);
+                if updated == *solver.constraints() {
+                    reporter.message(ALREADY_KNOWN);
+                    continue;
+                }
+
+                solver.exclude_at(position - 1, letter);
+                reporter.message(&format!(
+                    "Position {} is not '{}'. {} candidates remain.",
+                    position,
+                    letter as char,
+                    solver.candidates().len()
+                ));
+                break false;
+            }
+
+            if options.mastermind_feedback {
+                let feedback = match parts.as_slice() {
+                    [guess, greens, yellows] => {
+                        parse_counts(greens, yellows).map(|counts| (*guess, counts))
+                    }
+                    _ => None,
+                };
+
+                let Some((guess, counts)) = feedback else {
+                    reporter.message(&format!(
+                        "Invalid input. Please enter your guess and two counts (e.g. '{} 1 2').",
+                        sample
+                    ));
+                    continue;
+                };
+
+                if !word_lists.guesses.contains(&guess) {
+                    reporter.message(&format!("Guess '{}' is not a valid word.", guess));
+                    continue;
+                }
+
+                if counts.0 as usize == N {
+                    reporter.message(&format!(
+                        "Congratulations! You've guessed the word '{}'.",
+                        guess
+                    ));
+                    break true;
+                }
+
+                solver.apply_count_feedback(guess, counts);
+                if options.hint_level == HintLevel::None && !options.answer_only {
+                    reporter.message("Accepted.");
+                }
+
+                break false;
+            }
+
+            if parts.len() != 2 {
+                reporter.message("You must enter two words");
+                reporter.message(&format!(
+                    "Invalid input. Please enter your guess and result (e.g. '{} {}').",
+                    sample, example
+                ));
+                continue;
+            }
+            let guess = parts[0];
+            let result = parts[1];
+
+            if guess.len() != N || result.chars().count() != N {
+                reporter.message(&format!("Guess and result must be {} characters long.", N));
+                reporter.message(&format!(
+                    "Invalid input. Please enter your guess and result (e.g. '{} {}').",
+                    sample, example
+                ));
+                continue;
+            }
+
+            let Some(output) = parse_pattern_with(result, options.symbols) else {
+                let [green, yellow, gray] = options.symbols;
+                reporter.message(&format!(
+                    "Result must only contain '{}', '{}', or '{}'.",
+                    green, yellow, gray
+                ));
+                reporter.message(&format!(
+                    "Invalid input. Please enter your guess and result (e.g. '{} {}').",
+                    sample, example
+                ));
+                continue;
+            };
+
+            if !word_lists.guesses.contains(&guess) {
+                reporter.message(&format!("Guess '{}' is not a valid word.", guess));
+                reporter.message(&format!(
+                    "Invalid input. Please enter your guess and result (e.g. '{} {}').",
+                    sample, example
+                ));
+                continue;
+            }
+
+            let violation = solver.game().hard_mode_violation(guess);
+            if let (true, Some(violation)) = (options.solver.hard_mode, violation) {
+                reporter.message(&format!(
+                    "Guess '{}' is not allowed in hard mode: {}.",
+                    guess, violation
+                ));
+                continue;
+            }
+
+            if output
+                .iter()
+                .all(|&color| color == WordleAnswerColor::Green)
+            {
+                solver.apply_feedback(guess, output);
+                reporter.message(&format!(
+                    "Congratulations! You've guessed the word '{}'.",
+                    guess
+                ));
+                break true;
+            }
+
+            solver.apply_feedback(guess, output);
+            if options.hint_level == HintLevel::None && !options.answer_only {
+                reporter.message("Accepted.");
+            }
+
+            break false;
+        };
+
+        #[cfg(feature = "serde")]
+        if let Some(path) = &options.save_game {
+            if let Err(err) = save_game(path, &solver) {
+                reporter.error(&format!("Failed to save the game to '{}': {}", path, err));
+            }
+        }
+        if finished {
+            break;
+        }
+
+        match solver.candidates() {
+            [] => {
+                reporter.message("No valid words left. Please check your input.");
+                break;
+            }
+            [answer] if options.answer_only => {
+                reporter.message(&format!("The answer must be '{}'.", answer));
+                break;
+            }
+            _ => {}
+        }
+    }
+}
+
+// The green and yellow counts of Mastermind-style feedback, which can't add up to more than the
+// word has letters. They're added up as usize so that e.g. 200 and 100 can't wrap around as u8
+fn parse_counts(greens: &str, yellows: &str) -> Option<(u8, u8)> {
+    let greens: usize = greens.parse().ok()?;
+    let yellows: usize = yellows.parse().ok()?;
+    let total = greens.checked_add(yellows)?;
+    (total <= WORD_LENGTH).then_some((greens as u8, yellows as u8))
+}
+
+// Refuses to write anything rather than a game that wouldn't load back the same
+#[cfg(feature = "serde")]
+fn save_game<const N: usize>(path: &str, solver: &Solver<N>) -> Result<(), String> {
+    let saved = solver.saved_game()?;
+    let json = serde_json::to_string_pretty(&saved).map_err(|err| err.to_string())?;
+    std::fs::write(path, json).map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "serde")]
+fn load_game<const N: usize>(
+    word_lists: &WordLists,
+    path: &str,
+    solver: &mut Solver<N>,
+) -> Result<(), String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read '{}': {}", path, err))?;
+    let saved: SavedGame<N> = serde_json::from_str(&json)
+        .map_err(|err| format!("'{}' is not a saved game: {}", path, err))?;
+    if let Some(turn) = saved
+        .game
+        .turns()
+        .iter()
+        .find(|turn| !word_lists.guesses.contains(&turn.guess.as_str()))
+    {
+        return Err(format!("Guess '{}' is not a valid word.", turn.guess));
+    }
+    solver
+        .restore(saved)
+        .map_err(|err| format!("'{}': {}", path, err))
+}
+
+const REPEAT_PENALTY_DECAY: f64 = 0.5;
+
+// The full penalty for guesses suggested last puzzle, halving with every puzzle since
+fn repeat_penalties(session: &[Vec<&'static str>], weight: f64) -> HashMap<&'static str, f64> {
+    let mut penalties = HashMap::new();
+    for (age, puzzle) in session.iter().rev().enumerate() {
+        for &word in puzzle {
+            *penalties.entry(word).or_insert(0.) += weight * REPEAT_PENALTY_DECAY.powi(age as i32);
+        }
+    }
+    penalties
+}
+
+fn practice_answers(solutions: &[&'static str], difficulty: Difficulty) -> Vec<&'static str> {
+    match difficulty {
+        Difficulty::Normal => solutions.to_vec(),
+        Difficulty::Easy | Difficulty::Hard => {
+            let freq_data = letter_frequency::<WORD_LENGTH>(solutions);
+            let mut ranked = solutions.to_vec();
+            // Most likely first for easy, least likely first for hard
+            ranked.sort_by(|a, b| {
+                let order = word_likelihood_score(b, &freq_data)
+                    .total_cmp(&word_likelihood_score(a, &freq_data));
+                if difficulty == Difficulty::Hard {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+            ranked.truncate(ranked.len().div_ceil(3));
+            ranked
+        }
+    }
+}
+
+// A pattern written with the configured symbols instead of g/y/x
+fn symbol_string(pattern: &[WordleAnswerColor], [green, yellow, gray]: [char; 3]) -> String {
+    pattern
+        .iter()
+        .map(|color| match color {
+            WordleAnswerColor::Green => green,
+            WordleAnswerColor::Yellow => yellow,
+            WordleAnswerColor::Gray => gray,
+        })
+        .collect()
+}
+
+fn run_practice(word_lists: &WordLists, options: &Options, input: &mut dyn BufRead) {
+    let reporter = options.reporter.as_ref();
+    let all_words = &word_lists.guesses;
+    let solutions = &word_lists.solutions;
+
+    let answer = match options.answer.as_deref() {
+        Some(answer) => {
+            let answer = answer.to_lowercase();
+            if !all_words.contains(&answer.as_str()) {
+                reporter.message(&format!("Answer '{}' is not a valid word.", answer));
+                return;
+            }
+            answer
+        }
+        None => {
+            let seed = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .subsec_nanos() as usize;
+            let pool = practice_answers(solutions, options.difficulty);
+            pool[seed % pool.len()].to_owned()
+        }
+    };
+
+    reporter.message("Running Practice...");
+    reporter
+        .message("Enter a guess or 'exit' to quit. Results use g = green, y = yellow, x = gray.");
+
+    let mut patterns = Vec::new();
+
+    while patterns.len() < MAX_GUESSES {
+        std::io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if input.read_line(&mut line).unwrap() == 0 {
+            return;
+        }
+
+        let guess = line.trim().to_lowercase();
+
+        if guess == "exit" {
+            return;
+        }
+
+        if !all_words.contains(&guess.as_str()) {
+            reporter.message(&format!("Guess '{}' is not a valid word.", guess));
+            continue;
+        }
+
+        let output: [WordleAnswerColor; WORD_LENGTH] = simulate_guess(&answer, &guess);
+        patterns.push(output);
+        reporter.message(&format!("{} {}", guess, pattern_string(&output)));
+
+        if guess == answer {
+            reporter.message(&format!("Solved in {}/{}!", patterns.len(), MAX_GUESSES));
+            if options.share {
+                reporter.message(&format!("\n{}", share_grid(&patterns, true)));
+            }
+            return;
+        }
+    }
+
+    reporter.message(&format!("Out of guesses. The word was '{}'.", answer));
+    if options.share {
+        reporter.message(&format!("\n{}", share_grid(&patterns, false)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures::IGHT;
+
+    // Records every call as one line, so tests can check what was reported and in which order
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    impl Capture {
+        fn calls(&self) -> Vec<String> {
+            self.0.lock().unwrap().clone()
+        }
+
+        fn messages(&self) -> Vec<String> {
+            self.calls()
+                .into_iter()
+                .filter_map(|call| call.strip_prefix("message ").map(str::to_owned))
+                .collect()
+        }
+
+        fn record(&self, call: String) {
+            self.0.lock().unwrap().push(call);
+        }
+    }
+
+    impl Reporter for Capture {
+        fn progress(&self, done: usize, total: usize) {
+            self.record(format!("progress {}/{}", done, total));
+        }
+
+        fn message(&self, msg: &str) {
+            self.record(format!("message {}", msg));
+        }
+
+        fn status(&self, status: &str) {
+            self.record(format!("status {}", status));
+        }
+
+        fn error(&self, msg: &str) {
+            self.record(format!("error {}", msg));
+        }
+
+        fn prompt(&self, question: &str) {
+            self.record(format!("prompt {}", question));
+        }
+    }
+
+    const WORDS: [&str; 10] = [
+        "salet", "crane", "trace", "crate", "caret", "react", "slate", "least", "steal", "tales",
+    ];
+
+    fn word_lists() -> WordLists {
+        WordLists {
+            guesses: WORDS.to_vec(),
+            solutions: WORDS.to_vec(),
+        }
+    }
+
+    // The defaults, reporting to `capture` and without the bundled book, which is only built
+    // for the full word lists
+    fn test_options(capture: &Capture, args: &[&str]) -> Options {
+        let mut options = parse_args(args.iter().map(|arg| arg.to_string())).unwrap();
+        options.reporter = Box::new(capture.clone());
+        options.no_book = true;
+        options
+    }
+
+    #[test]
+    fn benchmark_reports_in_order() {
+        let capture = Capture::default();
+        let mut options = test_options(&capture, &[]);
+        options.sequential = true;
+        benchmark(&word_lists(), &WORDS[..2], &options);
+
+        let calls = capture.calls();
+        assert_eq!(calls[0], "message Running Benchmark...");
+        assert!(calls[1].starts_with("status 50.0% done"));
+        assert_eq!(calls[2], "progress 1/2");
+        assert!(calls[3].starts_with("status 100.0% done"));
+        assert_eq!(calls[4], "progress 2/2");
+        assert!(calls[5].starts_with("message 100.0% accuracy"));
+    }
+
+    #[test]
+    fn prompts_go_through_the_reporter() {
+        let capture = Capture::default();
+        assert!(prompt_yes_no(
+            &capture,
+            "Hard Mode? (y/n)",
+            &mut "Y\n".as_bytes()
+        ));
+        assert!(!prompt_yes_no(
+            &capture,
+            "Benchmark? (y/n)",
+            &mut "n\n".as_bytes()
+        ));
+        assert_eq!(
+            capture.calls(),
+            ["prompt Hard Mode? (y/n)", "prompt Benchmark? (y/n)"]
+        );
+    }
+
+    #[test]
+    fn practice_ends_on_the_correct_guess() {
+        let capture = Capture::default();
+        let options = test_options(&capture, &["--practice", "--answer", "crate"]);
+        run_practice(
+            &word_lists(),
+            &options,
+            &mut "salet\ncrate\ntrace\n".as_bytes(),
+        );
+
+        let messages = capture.messages();
+        assert_eq!(messages[messages.len() - 2], "crate ggggg");
+        assert_eq!(messages[messages.len() - 1], "Solved in 2/6!");
+    }
+
+    // The first `size` bundled solutions as both lists, big enough to give the solver real
+    // choices while staying quick in debug builds
+    fn sample_lists(size: usize) -> WordLists {
+        let words = parse_word_list(include_str!("solution_words.txt")).unwrap();
+        WordLists {
+            guesses: words[..size].to_vec(),
+            solutions: words[..size].to_vec(),
+        }
+    }
+
+    // The summary line, e.g. "100.0% accuracy 2.500 average attempts"
+    fn benchmark_summary(words: &WordLists, sequential: bool) -> String {
+        let capture = Capture::default();
+        let mut options = test_options(&capture, &[]);
+        options.sequential = sequential;
+        benchmark(words, &words.solutions, &options);
+
+        capture
+            .messages()
+            .into_iter()
+            .find(|message| message.contains("average attempts"))
+            .unwrap()
+    }
+
+    #[test]
+    fn parallel_benchmark_matches_sequential() {
+        let words = sample_lists(80);
+        assert_eq!(
+            benchmark_summary(&words, true),
+            benchmark_summary(&words, false)
+        );
+    }
+
+    // Runs the assister over `lines` and returns what it reported
+    fn assist(args: &[&str], lines: &str) -> Vec<String> {
+        let capture = Capture::default();
+        let options = test_options(&capture, args);
+        run_assister::<WORD_LENGTH>(&word_lists(), &options, &[], &mut lines.as_bytes());
+        capture.messages()
+    }
+
+    #[test]
+    fn exclude_narrows_the_candidates() {
+        let messages = assist(&[], "exclude s\nexclude s\nexclude n\n");
+        assert!(messages.contains(&"Excluded 's'. 5 candidates remain.".to_owned()));
+        assert!(messages.contains(&ALREADY_KNOWN.to_owned()));
+        assert!(messages.contains(&"Excluded 'n'. 4 candidates remain.".to_owned()));
+    }
+
+    #[test]
+    fn exclude_refuses_letters_known_to_be_present() {
+        let messages = assist(&[], "salet xyxyy\nexclude a\nexclude r\n");
+        assert!(messages
+            .contains(&"'a' is known to be in the answer, so it can't be excluded.".to_owned()));
+        assert!(messages
+            .contains(&"No candidates would be left after 'exclude r', ignoring it.".to_owned()));
+    }
+
+    #[test]
+    fn target_gap_is_signed_and_relative() {
+        assert_eq!(target_gap(3.5, 3.5), (0., 1.));
+
+        let (gap, percent) = target_gap(4., 3.);
+        assert_eq!(gap, 1.);
+        assert_eq!(percent, 0.75);
+
+        // Beating the target gives a negative gap and more than 100%
+        let (gap, percent) = target_gap(3., 4.);
+        assert_eq!(gap, -1.);
+        assert!(percent > 1.);
+    }
+
+    #[test]
+    fn counts_must_fit_in_the_word() {
+        assert_eq!(parse_counts("1", "2"), Some((1, 2)));
+        assert_eq!(parse_counts("5", "0"), Some((5, 0)));
+        assert_eq!(parse_counts("0", "0"), Some((0, 0)));
+        assert_eq!(parse_counts("3", "3"), None);
+        assert_eq!(parse_counts("6", "0"), None);
+        // Each fits in a u8 but the sum doesn't
+        assert_eq!(parse_counts("200", "100"), None);
+        assert_eq!(parse_counts("255", "1"), None);
+        assert_eq!(parse_counts(&usize::MAX.to_string(), "1"), None);
+        assert_eq!(parse_counts("-1", "2"), None);
+        assert_eq!(parse_counts("one", "2"), None);
+    }
+
+    #[test]
+    fn mastermind_feedback_narrows_the_candidates() {
+        let messages = assist(
+            &["--mastermind-feedback"],
+            "salet 1 4\nsalet 3 3\nsalet 200 100\n",
+        );
+        assert_eq!(
+            messages
+                .iter()
+                .filter(|message| message.starts_with("Invalid input"))
+                .count(),
+            2
+        );
+        assert!(messages.contains(&"Best guess: slate".to_owned()));
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn daily_answer_from_a_mocked_fetch_is_solved() {
+        let answer = ideal_wordle_player::daily::answer_for("2026-10-14", |url| {
+            assert!(url.ends_with("/2026-10-14.json"));
+            Ok(r#"{"id": 1, "solution": "Crate", "print_date": "2026-10-14"}"#.to_owned())
+        })
+        .unwrap();
+        assert_eq!(answer, "crate");
+
+        let capture = Capture::default();
+        run_solve(&word_lists(), &test_options(&capture, &[]), &answer);
+        assert!(capture
+            .messages()
+            .iter()
+            .any(|message| message.starts_with("Solved in")));
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn daily_fetch_errors_are_passed_on() {
+        let failed =
+            ideal_wordle_player::daily::answer_for("2026-10-14", |_| Err("offline".into()));
+        assert_eq!(failed, Err("offline".to_owned()));
+
+        let empty = ideal_wordle_player::daily::answer_for("2026-10-14", |_| Ok("{}".into()));
+        assert!(empty.unwrap_err().starts_with("No solution found"));
+    }
+
+    // Keeps what would have gone to stderr. Only one logger can be installed per process, so
+    // every test that logs shares it
+    struct CaptureLogger(Mutex<Vec<String>>);
+
+    static CAPTURE_LOGGER: CaptureLogger = CaptureLogger(Mutex::new(Vec::new()));
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::max_level()
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(log_line(record));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn verbosity_sets_what_is_logged() {
+        let _ = log::set_logger(&CAPTURE_LOGGER);
+        // Seven candidates, which no other test leaves, so their lines can be told apart
+        let words = [
+            "crate", "trace", "caret", "react", "crane", "slate", "least",
+        ];
+        let lines = || CAPTURE_LOGGER.0.lock().unwrap().clone();
+        let run = |args: &[&str]| {
+            log::set_max_level(test_options(&Capture::default(), args).verbosity);
+            let lists = WordLists {
+                guesses: words.to_vec(),
+                solutions: words.to_vec(),
+            };
+            let mut solver = Solver::new(&lists, SolverOptions::default());
+            solver.retain_candidates(|_| true);
+            solver.best_guess();
+        };
+
+        run(&["-v"]);
+        assert!(lines()
+            .iter()
+            .any(|line| line.starts_with("[DEBUG] turn 1: chose")
+                && line.ends_with("over 7 candidates")));
+        assert!(!lines().contains(&"[TRACE] 7 candidates and 7 guesses remain".to_owned()));
+
+        run(&["-vv"]);
+        assert!(lines().contains(&"[TRACE] 7 candidates and 7 guesses remain".to_owned()));
+        log::set_max_level(log::LevelFilter::Warn);
+    }
+
+    #[test]
+    fn hint_level_none_hides_the_best_guess() {
+        let lines = "salet xyxyy\nexit\n";
+        let shown = assist(&["--hint-level", "medium"], lines);
+        assert!(shown
+            .iter()
+            .any(|message| message.starts_with("Best guess: ")));
+
+        let hidden = assist(&["--hint-level", "none"], lines);
+        assert!(!hidden
+            .iter()
+            .any(|message| message.starts_with("Best guess: ")));
+        assert!(hidden.contains(&"Accepted.".to_owned()));
+    }
+
+    #[test]
+    fn benchmark_over_a_tiny_hard_word_set() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/testdata/hard_words.txt");
+        let targets = hard_word_targets(Some(path), &WORDS).unwrap();
+        assert_eq!(targets, ["caret", "react"]);
+        assert!(hard_word_targets(Some(path), &["crate"]).is_err());
+
+        let capture = Capture::default();
+        benchmark(&word_lists(), &targets, &test_options(&capture, &[]));
+        let calls = capture.calls();
+        assert!(calls.contains(&"progress 2/2".to_owned()));
+        assert!(capture
+            .messages()
+            .iter()
+            .any(|message| message.starts_with("100.0% accuracy")));
+    }
+
+    #[test]
+    fn eliminated_words_are_the_difference() {
+        let before = WORDS.to_vec();
+        let after = vec!["crate", "trace"];
+        let eliminated = eliminated_words(&before, &after);
+
+        assert_eq!(eliminated.len(), before.len() - after.len());
+        assert!(eliminated.iter().all(|word| !after.contains(word)));
+        assert!(before
+            .iter()
+            .all(|word| after.contains(word) != eliminated.contains(word)));
+        // In the order they had before
+        assert_eq!(eliminated[..3], ["salet", "crane", "caret"]);
+
+        let messages = assist(&["--show-eliminated"], "salet xyxyy\nexit\n");
+        assert!(messages.contains(
+            &"Eliminated 8 words: salet crane caret react slate least steal tales".to_owned()
+        ));
+    }
+
+    #[test]
+    fn fail_fast_stops_at_the_first_failure() {
+        let words = WordLists {
+            guesses: IGHT.to_vec(),
+            solutions: IGHT.to_vec(),
+        };
+        let capture = Capture::default();
+        let options = test_options(&capture, &["--fail-fast"]);
+        benchmark(&words, &IGHT, &options);
+
+        let messages = capture.messages();
+        let failure = messages
+            .iter()
+            .position(|message| message.starts_with("Failed to solve"))
+            .unwrap();
+        // Only the failed game's transcript comes after it
+        assert!(messages[failure + 1..]
+            .iter()
+            .all(|message| message.ends_with(" bits)")));
+        assert!(!messages.iter().any(|message| message.contains("accuracy")));
+        assert!(!capture
+            .calls()
+            .contains(&format!("progress {0}/{0}", IGHT.len())));
+    }
+
+    #[test]
+    fn replay_compares_each_turn_to_the_solver() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/testdata/transcript.txt");
+        let transcript = load_transcript(path, DEFAULT_SYMBOLS, &WORDS).unwrap();
+        assert_eq!(transcript.len(), 3);
+
+        let capture = Capture::default();
+        run_replay(&word_lists(), &test_options(&capture, &[]), &transcript);
+        let messages = capture.messages();
+
+        assert!(messages[0].starts_with("Turn 1: salet ("));
+        assert!(messages[0].contains("matches the solver"));
+        assert!(messages[1].starts_with("Turn 2: trace ("));
+        assert!(messages[2].starts_with("Turn 3: crate ("));
+        assert!(messages[3].starts_with("Overall grade: "));
+        assert!(messages[3].ends_with(" over 3 turns"));
+    }
+
+    #[test]
+    fn bench_all_solves_every_guess_word() {
+        let lists = WordLists {
+            guesses: WORDS.to_vec(),
+            solutions: WORDS[..3].to_vec(),
+        };
+        let lists = every_guess_an_answer(&lists);
+        assert_eq!(lists.solutions, WORDS);
+
+        let capture = Capture::default();
+        let mut options = test_options(&capture, &[]);
+        options.sequential = true;
+        benchmark(&lists, &lists.solutions, &options);
+        assert!(capture
+            .calls()
+            .contains(&format!("progress {0}/{0}", WORDS.len())));
+    }
+
+    #[test]
+    fn computed_opener_splits_a_tiny_dictionary_best() {
+        // Only "crate" tells all four apart
+        let lists = WordLists {
+            guesses: vec!["fuzzy", "jazzy", "crate", "kiosk"],
+            solutions: vec!["crate", "trace", "slate", "grate"],
+        };
+        assert_eq!(best_opener(&lists), Some("crate"));
+
+        let capture = Capture::default();
+        compute_opener(&lists, &capture);
+        let messages = capture.messages();
+        assert_eq!(messages[1], "crate 2.000 bits");
+        assert_eq!(messages.last().unwrap(), "Best opener: crate");
+    }
+
+    // Sets the flag once `after` words are done, like a Ctrl-C in the middle of a benchmark
+    struct InterruptAfter {
+        capture: Capture,
+        flag: Arc<AtomicBool>,
+        after: usize,
+    }
+
+    impl Reporter for InterruptAfter {
+        fn progress(&self, done: usize, total: usize) {
+            self.capture.progress(done, total);
+            if done >= self.after {
+                self.flag.store(true, Ordering::Relaxed);
+            }
+        }
+
+        fn message(&self, msg: &str) {
+            self.capture.message(msg);
+        }
+    }
+
+    #[test]
+    fn interrupted_benchmark_reports_the_words_done() {
+        let capture = Capture::default();
+        let flag = Arc::new(AtomicBool::new(false));
+        let mut options = test_options(&capture, &[]);
+        options.sequential = true;
+        options.reporter = Box::new(InterruptAfter {
+            capture: capture.clone(),
+            flag: flag.clone(),
+            after: 3,
+        });
+        benchmark_until::<WORD_LENGTH>(&word_lists(), &WORDS, &options, &flag);
+
+        let messages = capture.messages();
+        assert!(messages.contains(&"Interrupted after 3 of 10 words".to_owned()));
+        assert!(messages
+            .iter()
+            .any(|message| message.starts_with("100.0% accuracy")));
+        assert!(!capture.calls().contains(&"progress 4/10".to_owned()));
+    }
+
+    #[test]
+    fn not_rules_a_letter_out_at_one_position() {
+        let messages = assist(&[], "not 1 s\nnot 1 s\nnot 6 s\nnot 1 S\n");
+        assert!(messages.contains(&"Position 1 is not 's'. 7 candidates remain.".to_owned()));
+        assert!(messages.contains(&ALREADY_KNOWN.to_owned()));
+        assert_eq!(
+            messages
+                .iter()
+                .filter(|message| message.starts_with("Usage: not"))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn formatting_helpers_are_fixed_precision() {
+        assert_eq!(format_bits(5.8357), "5.836 bits");
+        assert_eq!(format_bits(-0.), "0.000 bits");
+        assert_eq!(format_attempts(3.4279), "3.428");
+        assert_eq!(format_percent(0.9975), "99.8%");
+        assert_eq!(format_percent(-0.), "0.0%");
+        assert_eq!(format_probability(0.00123), "0.12%");
+        assert_eq!(format_score(-0.), "0.000");
+        assert_eq!(format_score(-1.2345), "-1.234");
+        assert_eq!(format_signed(0.0071), "+0.007");
+        assert_eq!(format_signed(-0.25), "-0.250");
+        assert_eq!(format_signed(-0.), "+0.000");
+    }
+
+    #[test]
+    fn seeded_state_with_one_candidate_announces_it() {
+        let capture = Capture::default();
+        let options = test_options(&capture, &[]);
+        let seed = [("salet".to_owned(), simulate_guess("crane", "salet"))];
+        run_assister::<WORD_LENGTH>(&word_lists(), &options, &seed, &mut "crate\n".as_bytes());
+
+        let messages = capture.messages();
+        assert_eq!(messages.last().unwrap(), "The answer must be 'crane'.");
+    }
+
+    #[test]
+    fn one_thread_gives_the_same_benchmark() {
+        let words = sample_lists(80);
+        let single = with_threads(Some(1), || {
+            assert_eq!(rayon::current_num_threads(), 1);
+            benchmark_summary(&words, false)
+        });
+        assert_eq!(single.unwrap(), benchmark_summary(&words, false));
+    }
+
+    #[test]
+    fn intervals_match_known_values() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-4;
+
+        let (low, high) = wilson_interval(8, 10);
+        assert!(close(low, 0.4902) && close(high, 0.9433));
+        // Unlike the normal approximation, a perfect score still gets a lower bound below 1
+        let (low, high) = wilson_interval(5, 5);
+        assert!(close(low, 0.5655) && close(high, 1.));
+
+        let (mean, margin) = mean_interval(&[2., 3., 4.]);
+        assert!(close(mean, 3.) && close(margin, 1.96 / 3f64.sqrt()));
+        assert_eq!(mean_interval(&[4.]), (4., 0.));
+    }
+
+    #[test]
+    fn opener_stats_buckets_cover_every_solution() {
+        let capture = Capture::default();
+        let mut words = sample_lists(500);
+        words.guesses.push("salet");
+        opener_stats(&words, &capture, "salet");
+
+        let messages = capture.messages();
+        let (summary, rows) = messages.split_last().unwrap();
+        let total: usize = rows
+            .iter()
+            .map(|row| row.split_once(' ').unwrap().1.parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(total, words.solutions.len());
+        assert!(summary.ends_with(&format!("of {} solutions left", total)));
+    }
+
+    #[test]
+    fn piped_game_plays_every_line() {
+        let lines: String = ["salet", "trace", "crate"]
+            .iter()
+            .map(|guess| {
+                format!(
+                    "{} {}\n",
+                    guess,
+                    pattern_string(&simulate_guess::<WORD_LENGTH>("crate", guess))
+                )
+            })
+            .collect();
+        let messages = assist(&[], &lines);
+
+        assert!(messages.contains(&"Congratulations! You've guessed the word 'crate'.".to_owned()));
+        let hints = messages
+            .iter()
+            .filter(|message| message.starts_with("Best guess: "))
+            .count();
+        assert_eq!(hints, 3);
+        assert!(!messages
+            .iter()
+            .any(|message| message.starts_with("Invalid input")));
+    }
+
+    #[test]
+    fn answer_command_ends_the_session_with_a_summary() {
+        let messages = assist(&[], "salet xyxyy\nanswer salet\nanswer crate\nexit\n");
+        let solved = messages
+            .iter()
+            .position(|message| message == "Solved in 2/6 with 'crate'.")
+            .unwrap();
+
+        assert!(messages[..solved]
+            .contains(&"Usage: answer <word> (one of the remaining candidates)".to_owned()));
+        assert!(messages[solved + 1].starts_with("\nWordle 2/6"));
+    }
+
+    // A path in the system temp directory that's unique to this test run
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("wordlebot-{}-{}", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn dump_trees_writes_one_tree_per_word() {
+        let words = sample_lists(30);
+        let path = temp_path("trees.jsonl");
+        let capture = Capture::default();
+        let options = test_options(&capture, &["--dump-trees", &path]);
+        benchmark(&words, &words.solutions, &options);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut answers: Vec<String> = contents
+            .lines()
+            .map(|line| {
+                let tree: serde_json::Value = serde_json::from_str(line).unwrap();
+                assert!(!tree["turns"].as_array().unwrap().is_empty());
+                tree["answer"].as_str().unwrap().to_owned()
+            })
+            .collect();
+        answers.sort();
+
+        let mut expected = words.solutions.clone();
+        expected.sort();
+        assert_eq!(answers, expected);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_filter_narrows_the_candidates() {
+        let messages = assist(&[], "filter ^z\nfilter ^cr\nfilter a\n");
+        assert!(messages.contains(&"No candidates match '^z', ignoring it.".to_owned()));
+        assert!(messages.contains(&"Filtered by '^cr'. 2 candidates remain.".to_owned()));
+        assert!(messages.contains(&ALREADY_KNOWN.to_owned()));
+    }
+
+    #[test]
+    fn answer_only_prints_just_the_answer() {
+        let lines = format!(
+            "salet {}\n",
+            pattern_string(&simulate_guess::<WORD_LENGTH>("crane", "salet"))
+        );
+        let messages = assist(&["--answer-only"], &lines);
+
+        assert!(!messages
+            .iter()
+            .any(|message| message.starts_with("Best guess")
+                || message.ends_with("candidates remain.")
+                || message == "Accepted."));
+        assert_eq!(messages.last().unwrap(), "The answer must be 'crane'.");
+    }
+
+    #[test]
+    fn input_of_the_wrong_length_is_rejected() {
+        let messages = assist(&[], "salets xyxyyx\nsalet xyxy\n");
+        let rejected = messages
+            .iter()
+            .filter(|message| *message == "Guess and result must be 5 characters long.")
+            .count();
+        assert_eq!(rejected, 2);
+    }
+
+    #[test]
+    fn six_letter_words_reject_five_letter_input() {
+        let words = ["planet", "plants", "palest", "pastel", "staple", "petals"];
+        let lists = WordLists {
+            guesses: words.to_vec(),
+            solutions: words.to_vec(),
+        };
+        let result = pattern_string(&simulate_guess::<6>("staple", "planet"));
+        let lines = format!("salet xyxyy\nplane xyxyy\nplanet {}\nexit\n", result);
+        let capture = Capture::default();
+        let options = test_options(&capture, &[]);
+        run_assister::<6>(&lists, &options, &[], &mut lines.as_bytes());
+        let messages = capture.messages();
+
+        let rejected = messages
+            .iter()
+            .filter(|message| *message == "Guess and result must be 6 characters long.")
+            .count();
+        assert_eq!(rejected, 2);
+        assert!(messages.contains(
+            &"Invalid input. Please enter your guess and result (e.g. 'planet ggyyxg').".to_owned()
+        ));
+        // Only the six letter turn is played, so a guess is suggested before and after it
+        let suggestions = messages
+            .iter()
+            .filter(|message| message.starts_with("Best guess: "))
+            .count();
+        assert_eq!(suggestions, 2);
+    }
+
+    #[test]
+    fn contains_lists_the_matching_candidates() {
+        let messages = assist(&[], "contains ls\ncontains cn\ncontains ee\n");
+        assert!(messages.contains(
+            &"5 of 10 candidates contain 'ls': salet slate least steal tales".to_owned()
+        ));
+        assert!(messages.contains(&"1 of 10 candidates contain 'cn': crane".to_owned()));
+        assert!(messages.contains(&"0 of 10 candidates contain 'ee': ".to_owned()));
+    }
+
+    #[test]
+    fn difficulty_picks_from_the_expected_end() {
+        let freq_data = letter_frequency::<WORD_LENGTH>(&WORDS);
+        let likelihood = |word: &str| word_likelihood_score(word, &freq_data);
+
+        let easy = practice_answers(&WORDS, Difficulty::Easy);
+        assert_eq!(easy.len(), 4);
+        let rest = WORDS.iter().filter(|word| !easy.contains(word));
+        let least_common_easy = easy
+            .iter()
+            .map(|word| likelihood(word))
+            .fold(f64::MAX, f64::min);
+        assert!(rest
+            .into_iter()
+            .all(|word| likelihood(word) <= least_common_easy));
+
+        let hard = practice_answers(&WORDS, Difficulty::Hard);
+        assert_eq!(hard.len(), 4);
+        let rest = WORDS.iter().filter(|word| !hard.contains(word));
+        let most_common_hard = hard
+            .iter()
+            .map(|word| likelihood(word))
+            .fold(f64::MIN, f64::max);
+        assert!(rest
+            .into_iter()
+            .all(|word| likelihood(word) >= most_common_hard));
+    }
+
+    #[test]
+    fn flags_override_the_environment_which_overrides_defaults() {
+        let env = |name: &str| match name {
+            "WORDLEBOT_VALID_BIAS" => Some("0.5".to_owned()),
+            "WORDLEBOT_THREADS" => Some("3".to_owned()),
+            "WORDLEBOT_FIRST_GUESS" => Some("crane".to_owned()),
+            _ => None,
+        };
+        let args = ["--valid-bias", "0.7"].into_iter().map(String::from);
+        let options = resolve_options(env, args).unwrap();
+
+        assert_eq!(options.solver.weights.valid_bias, 0.7);
+        assert_eq!(options.threads, Some(3));
+        assert_eq!(options.first_guess.as_deref(), Some("crane"));
+        assert_eq!(
+            options.solver.weights.coverage,
+            ScoringWeights::default().coverage
+        );
+        assert_eq!(options.first_guess_hard, None);
+
+        let args = ["--first-guess", "trace"].into_iter().map(String::from);
+        let options = resolve_options(env, args).unwrap();
+        assert_eq!(options.first_guess.as_deref(), Some("trace"));
+    }
+
+    #[test]
+    fn bad_environment_values_name_the_variable() {
+        let resolve = |name: &'static str, value: &'static str| {
+            let env = move |var: &str| (var == name).then(|| value.to_owned());
+            resolve_options(env, std::iter::empty()).err()
+        };
+
+        assert_eq!(
+            resolve("WORDLEBOT_THREADS", "abc").as_deref(),
+            Some("Invalid value 'abc' for WORDLEBOT_THREADS")
+        );
+        assert_eq!(
+            resolve("WORDLEBOT_THREADS", "0").as_deref(),
+            Some("WORDLEBOT_THREADS must be at least 1")
+        );
+        assert_eq!(
+            resolve("WORDLEBOT_VALID_BIAS", "high").as_deref(),
+            Some("Invalid value 'high' for WORDLEBOT_VALID_BIAS")
+        );
+        assert_eq!(
+            resolve_options(|_| None, ["--threads", "abc"].into_iter().map(String::from)).err(),
+            Some("Invalid value 'abc' for --threads".to_owned())
+        );
+    }
+
+    #[test]
+    fn startup_exclusion_narrows_the_candidates() {
+        let capture = Capture::default();
+        let options = test_options(&capture, &["--exclude", "sn"]);
+        let solver = new_solver(&word_lists(), &options);
+        assert_eq!(solver.candidates(), ["trace", "crate", "caret", "react"]);
+
+        let args = ["--exclude", "S"].into_iter().map(String::from);
+        assert!(parse_args(args).is_err());
+    }
+
+    #[test]
+    fn margins_report_the_gap_to_the_runner_up() {
+        let words = sample_lists(100);
+        let answer = words.solutions[7];
+        let capture = Capture::default();
+        let options = test_options(&capture, &["--margin-epsilon", "1000"]);
+        run_margins(&words, &options, answer);
+
+        let mut solver = new_solver(&words, &options);
+        let opener = solver.best_guess();
+        solver.apply_feedback(opener, simulate_guess(answer, opener));
+        let [(best, fitness), (runner_up, runner_up_fitness)] = solver.top_guesses(2)[..] else {
+            panic!("expected two guesses");
+        };
+
+        let messages = capture.messages();
+        assert!(messages[0].ends_with("(fixed opener)"));
+        assert!(messages[1].contains(&format!(
+            "(best {} by {} over {}, near tie",
+            best,
+            format_score(fitness - runner_up_fitness),
+            runner_up
+        )));
+    }
+
+    #[test]
+    fn custom_symbols_parse_patterns() {
+        let args = ["--symbols", "210"].into_iter().map(String::from);
+        let symbols = parse_args(args).unwrap().symbols;
+        assert_eq!(symbols, ['2', '1', '0']);
+        assert_eq!(
+            parse_pattern_with::<WORD_LENGTH>("01012", symbols),
+            parse_pattern("xyxyg")
+        );
+        assert_eq!(parse_pattern_with::<WORD_LENGTH>("xyxyg", symbols), None);
+
+        let args = ["--symbols", "gyg"].into_iter().map(String::from);
+        assert!(parse_args(args).is_err());
+
+        let messages = assist(&["--symbols", "210", "--show-eliminated"], "salet 01011\n");
+        assert!(messages.contains(
+            &"Eliminated 8 words: salet crane caret react slate least steal tales".to_owned()
+        ));
+    }
+
+    #[test]
+    fn two_player_finds_a_preset_secret() {
+        let capture = Capture::default();
+        let options = test_options(&capture, &[]);
+        let mut secrets = ["zzzzz\n", "Crate\n"]
+            .into_iter()
+            .map(|line| Ok(line.to_owned()));
+        run_two_player(&word_lists(), &options, &mut || secrets.next().unwrap());
+
+        let messages = capture.messages();
+        assert_eq!(
+            messages[0],
+            "That's not in the solution list, try another word."
+        );
+        assert!(messages
+            .last()
+            .unwrap()
+            .starts_with("The bot found 'crate' in "));
+        let prompts = capture
+            .calls()
+            .iter()
+            .filter(|call| call.starts_with("prompt "))
+            .count();
+        assert_eq!(prompts, 2);
+    }
+
+    #[test]
+    fn invalid_input_reuses_the_suggestion() {
+        let lines = "salet xyxyy\nfoo\nsalet xyxy\nzzzzz xxxxx\nexclude s\nfilter .\n";
+        let messages = assist(&[], lines);
+        let hints = messages
+            .iter()
+            .filter(|message| message.starts_with("Best guess: "))
+            .count();
+        // Once at the start and once after the one turn that changed anything
+        assert_eq!(hints, 2);
+    }
+
+    // A result that played `patterns` in order, guessing "salet" every time
+    fn played(patterns: &[&str]) -> SolveResult {
+        let mut game = Game::new();
+        for pattern in patterns {
+            game.push(Turn {
+                guess: "salet".to_owned(),
+                pattern: parse_pattern(pattern).unwrap(),
+                remaining_before: 10,
+                remaining_after: 1,
+            });
+        }
+        SolveResult {
+            entropies: Vec::new(),
+            game,
+        }
+    }
+
+    #[test]
+    fn pattern_stats_average_by_first_pattern() {
+        let results = [
+            ("a", played(&["xxxxx", "ggggg"])),
+            ("b", played(&["xxxxx", "xyxyy", "xyxyy", "ggggg"])),
+            ("c", played(&["ggggg"])),
+            ("d", played(&["xyxyy", "ggggg"])),
+        ];
+        let stats = pattern_stats(&results);
+
+        assert_eq!(
+            stats,
+            [
+                (parse_pattern("xxxxx").unwrap(), 2, 3.),
+                (parse_pattern("xyxyy").unwrap(), 1, 2.),
+                (parse_pattern("ggggg").unwrap(), 1, 1.),
+            ]
+        );
+    }
+
+    #[test]
+    fn candidates_and_rank_list_the_likeliest_first() {
+        let mut words = sample_lists(300);
+        words.guesses.push("salet");
+        let pattern = pattern_string(&simulate_guess::<WORD_LENGTH>(words.solutions[3], "salet"));
+        let listing = |command: &str| {
+            let capture = Capture::default();
+            let options = test_options(&capture, &[]);
+            let lines = format!("salet {}\n{}\n", pattern, command);
+            run_assister::<WORD_LENGTH>(&words, &options, &[], &mut lines.as_bytes());
+
+            let messages = capture.messages();
+            let start = messages
+                .iter()
+                .rposition(|m| m.starts_with("Best guess"))
+                .unwrap();
+            messages[start + 1..].to_vec()
+        };
+
+        let listed = listing("candidates");
+        assert_eq!(listing("rank"), listed);
+        assert!(listed.len() > 2);
+
+        let probabilities: Vec<f64> = listed
+            .iter()
+            .filter_map(|line| line.split_once(' ')?.1.strip_suffix('%')?.parse().ok())
+            .collect();
+        assert_eq!(probabilities.len(), listed.len());
+        assert!(probabilities.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert!(probabilities[0] > probabilities[probabilities.len() - 1]);
+    }
+
+    #[test]
+    fn missing_opener_is_replaced_by_a_computed_one() {
+        let words = WordLists {
+            guesses: WORDS[1..].to_vec(),
+            solutions: WORDS[1..].to_vec(),
+        };
+        let capture = Capture::default();
+        let mut options = test_options(&capture, &[]);
+        resolve_openers(&words, &mut options).unwrap();
+
+        let best = best_opener(&words).unwrap();
+        assert_eq!(options.solver.first_guess, Some(best));
+        assert_eq!(options.solver.first_guess_hard, Some(best));
+        assert_eq!(
+            capture.messages(),
+            [format!(
+                "First guess 'salet' is not in the guess list, opening with '{}' instead",
+                best
+            )]
+        );
+        assert_eq!(new_solver(&words, &options).opener(), Some(best));
+
+        let empty = WordLists {
+            guesses: Vec::new(),
+            solutions: WORDS.to_vec(),
+        };
+        assert!(resolve_openers(&empty, &mut test_options(&capture, &[])).is_err());
+    }
+
+    #[test]
+    fn repeat_penalties_decay_with_age() {
+        let session = vec![vec!["salet", "crane"], vec!["salet"], vec!["trace"]];
+        let penalties = repeat_penalties(&session, 0.4);
+
+        assert_eq!(penalties["trace"], 0.4);
+        assert_eq!(penalties["salet"], 0.4 * 0.5 + 0.4 * 0.25);
+        assert_eq!(penalties["crane"], 0.4 * 0.25);
+    }
+
+    #[test]
+    fn hard_mode_replay_stops_at_an_illegal_turn() {
+        let transcript = [
+            ("salet".to_owned(), parse_pattern("xyxyy").unwrap()),
+            // Leaves out the yellow 't'
+            ("crane".to_owned(), parse_pattern("yyyxg").unwrap()),
+            ("crate".to_owned(), parse_pattern("ggggg").unwrap()),
+        ];
+
+        let capture = Capture::default();
+        let mut options = test_options(&capture, &[]);
+        options.solver.hard_mode = true;
+        run_replay(&word_lists(), &options, &transcript);
+        let messages = capture.messages();
+
+        assert!(messages[0].starts_with("Turn 1: salet ("));
+        assert_eq!(
+            messages[1],
+            "Turn 2: 'crane' breaks hard mode, it must contain 't'."
+        );
+        assert_eq!(messages.len(), 2);
+
+        // The same transcript is fine outside hard mode
+        let capture = Capture::default();
+        run_replay(&word_lists(), &test_options(&capture, &[]), &transcript);
+        assert!(capture.messages()[1].starts_with("Turn 2: crane ("));
+    }
+
+    #[test]
+    fn book_and_runtime_agree_on_turn_two() {
+        let lists = WordLists::load(None, None).unwrap();
+        let capture = Capture::default();
+        book_check(&lists, &test_options(&capture, &[]), "vivid");
+        let messages = capture.messages();
+
+        assert_eq!(messages[0], "Book check: turn 1 salet agrees with the book");
+        assert_eq!(messages[1], "Book check: turn 2 crony agrees with the book");
+        assert!(messages[2]
+            .starts_with("Book check: with and without the book the solver plays the same"));
+    }
+
+    #[test]
+    fn dump_freq_writes_one_row_per_letter_and_position() {
+        let solver = new_solver(&word_lists(), &test_options(&Capture::default(), &[]));
+        let frequencies = solver.frequencies();
+        let path = temp_path("freq.csv");
+        dump_freq(&path, frequencies).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("position,letter,probability"));
+        let rows: Vec<(usize, char)> = lines
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+                assert_eq!(fields.len(), 3);
+                (fields[0].parse().unwrap(), fields[1].parse().unwrap())
+            })
+            .collect();
+
+        let mut expected: Vec<(usize, char)> = frequencies
+            .iter()
+            .enumerate()
+            .flat_map(|(i, position)| position.keys().map(move |&c| (i + 1, c as char)))
+            .collect();
+        expected.sort();
+        // Already in order, so the dump is the same every time
+        assert_eq!(rows, expected);
+        // First letters of the test words are s, c, t, r, l
+        assert_eq!(
+            rows.iter().filter(|&&(position, _)| position == 1).count(),
+            5
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_lines_turns_drive_the_assister() {
+        let capture = Capture::default();
+        let options = test_options(&capture, &["--json"]);
+        let lines = concat!(
+            "{\"guess\": \"salet\", \"pattern\": \"xyxyy\"}\n",
+            "{\"guess\": \"trace\"}\n",
+            "{\"guess\": \"trace\", \"pattern\": \"ggg\"}\n",
+            "not json {\n",
+            "{\"pattern\": \"yggyg\", \"guess\": \"TRACE\"}\n",
+            "state\n",
+            "exit\n",
+        );
+        run_assister::<WORD_LENGTH>(&word_lists(), &options, &[], &mut lines.as_bytes());
+
+        let errors: Vec<String> = capture
+            .calls()
+            .into_iter()
+            .filter_map(|call| call.strip_prefix("error ").map(str::to_owned))
+            .collect();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("missing field `pattern`"));
+        assert_eq!(errors[1], "Invalid pattern 'ggg', expected 5 of g, y and x");
+
+        let mut expected = Constraints::new();
+        expected.update_from_guess("salet", parse_pattern("xyxyy").unwrap());
+        expected.update_from_guess("trace", parse_pattern("yggyg").unwrap());
+        // "not json {" isn't a JSON line, so it gets the usual text handling instead
+        assert!(capture.messages().contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn bench_verbose_lists_the_turns_of_flagged_words() {
+        let verbose_lines = |threshold: &str| {
+            let capture = Capture::default();
+            let mut options = test_options(&capture, &["--bench-verbose", threshold]);
+            options.sequential = true;
+            benchmark(&word_lists(), &WORDS[..4], &options);
+            capture
+                .messages()
+                .into_iter()
+                .filter(|message| message.contains(" left)"))
+                .collect::<Vec<_>>()
+        };
+
+        // Every solve takes at least one guess, so all of them are flagged
+        let lines = verbose_lines("1");
+        assert_eq!(
+            lines,
+            [
+                "salet: salet (1 left)",
+                "crane: salet (1 left), crane (1 left)",
+                "trace: salet (2 left), crate (1 left), trace (1 left)",
+                "crate: salet (2 left), crate (1 left)",
+            ]
+        );
+        // Only trace took three guesses
+        assert_eq!(verbose_lines("3"), [lines[2].clone()]);
+        assert!(verbose_lines("7").is_empty());
+    }
+
+    #[test]
+    fn unlimited_solve_reports_the_full_count() {
+        let words = WordLists {
+            guesses: IGHT.to_vec(),
+            solutions: IGHT.to_vec(),
+        };
+        let summaries: Vec<String> = IGHT
+            .iter()
+            .map(|answer| {
+                let capture = Capture::default();
+                run_solve(&words, &test_options(&capture, &["--unlimited"]), answer);
+                capture
+                    .messages()
+                    .into_iter()
+                    .find(|message| message.starts_with("Solved in "))
+                    .unwrap()
+            })
+            .collect();
+
+        let past: Vec<&String> = summaries
+            .iter()
+            .filter(|summary| summary.ends_with(", past the limit of 6"))
+            .collect();
+        assert!(!past.is_empty());
+        for summary in past {
+            let count: usize = summary["Solved in ".len()..summary.find(" guesses").unwrap()]
+                .parse()
+                .unwrap();
+            assert!((7..=IGHT.len()).contains(&count), "{}", summary);
+        }
+    }
+
+    #[test]
+    fn blacklisted_words_are_never_suggested() {
+        let blacklist = ["salet", "crane", "notaword"];
+        let mut words = word_lists();
+        assert_eq!(words.blacklist(&blacklist), (2, 2));
+        assert!(!words.guesses.contains(&"salet") && !words.solutions.contains(&"crane"));
+
+        let capture = Capture::default();
+        let mut options = test_options(&capture, &[]);
+        resolve_openers(&words, &mut options).unwrap();
+        for &answer in &words.solutions {
+            let result = new_solver(&words, &options).solve(answer);
+            assert!(result.solved(), "{}", answer);
+            for guess in result.guesses() {
+                assert!(
+                    !blacklist.contains(&guess),
+                    "{} played for {}",
+                    guess,
+                    answer
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn custom_solution_benchmark_opens_with_a_computed_opener() {
+        let words = WordLists {
+            guesses: WORDS.to_vec(),
+            solutions: vec!["crane", "trace", "crate", "caret", "react"],
+        };
+        let best = best_opener(&words).unwrap();
+        // salet is in the guess list but isn't the best opener for these answers
+        assert_ne!(best, "salet");
+
+        let capture = Capture::default();
+        let mut options = test_options(
+            &capture,
+            &["--solution-words", "custom.txt", "--bench-verbose", "1"],
+        );
+        resolve_openers(&words, &mut options).unwrap();
+        assert_eq!(
+            capture.messages(),
+            [format!(
+                "Opening with '{}', the best opener for these solutions",
+                best
+            )]
+        );
+
+        options.sequential = true;
+        benchmark(&words, &words.solutions, &options);
+        let solves: Vec<String> = capture
+            .messages()
+            .into_iter()
+            .filter(|message| message.contains(" left)"))
+            .collect();
+        assert_eq!(solves.len(), words.solutions.len());
+        for solve in &solves {
+            let (_, turns) = solve.split_once(": ").unwrap();
+            assert!(turns.starts_with(&format!("{} (", best)), "{}", solve);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn assister_resumes_a_saved_game() {
+        let path = temp_path("saved-game.json");
+        let capture = Capture::default();
+        let options = test_options(&capture, &["--save-game", &path]);
+        run_assister::<WORD_LENGTH>(
+            &word_lists(),
+            &options,
+            &[],
+            &mut "crane xxyxy\nnot 5 t\nexit\n".as_bytes(),
+        );
+        let saved: SavedGame =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.game.turns().len(), 1);
+
+        let capture = Capture::default();
+        let options = test_options(&capture, &["--load-game", &path]);
+        run_assister::<WORD_LENGTH>(
+            &word_lists(),
+            &options,
+            &[],
+            &mut "state\nexit\n".as_bytes(),
+        );
+        std::fs::remove_file(&path).unwrap();
+        let messages = capture.messages();
+        // salet is ruled out by the 'not', which only the saved constraints know about
+        assert!(
+            messages.contains(&"2 candidates remain.".to_owned()),
+            "{:?}",
+            messages
+        );
+        assert!(messages.contains(&saved.constraints.to_string()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn unsavable_games_are_refused_with_a_message() {
+        let path = temp_path("unsavable.json");
+        let capture = Capture::default();
+        let options = test_options(&capture, &["--save-game", &path]);
+        let mut solver = new_solver(&word_lists(), &options);
+        solver.retain_candidates(|word| word != "crate");
+        assert!(save_game(&path, &solver)
+            .unwrap_err()
+            .contains("can't record"));
+        assert!(!std::path::Path::new(&path).exists());
+
+        assert_eq!(
+            parse_args(
+                ["--load-game", "a", "--state", "b"]
+                    .map(str::to_owned)
+                    .into_iter()
+            )
+            .err(),
+            Some("--load-game and --state can't be used together".to_owned())
+        );
+    }
+
+    #[test]
+    fn remaining_stats_aggregate_each_turn() {
+        let capture = Capture::default();
+        let mut options = test_options(&capture, &["--remaining-stats"]);
+        options.sequential = true;
+        benchmark(&word_lists(), &WORDS[..4], &options);
+
+        // The four games leave 1, 1/1, 2/1/1 and 2/1 candidates after each guess
+        let messages = capture.messages();
+        let start = messages
+            .iter()
+            .position(|message| message == "Candidates left after each guess:")
+            .unwrap();
+        assert_eq!(
+            messages[start + 1..start + 4],
+            [
+                "Guess 1: 4 games, median 1, p90 2, max 2",
+                "Guess 2: 3 games, median 1, p90 1, max 1",
+                "Guess 3: 1 games, median 1, p90 1, max 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn remaining_histograms_only_count_the_turns_reached() {
+        let results = [
+            ("a", played(&["xxxxx", "ggggg"])),
+            ("b", played(&["ggggg"])),
+            ("c", played(&["xxxxx", "xyxxx", "ggggg"])),
+        ];
+        assert_eq!(
+            remaining_histograms(&results),
+            [
+                BTreeMap::from([(1, 3)]),
+                BTreeMap::from([(1, 2)]),
+                BTreeMap::from([(1, 1)]),
+            ]
+        );
+        assert!(remaining_histograms::<WORD_LENGTH>(&[]).is_empty());
+    }
+
+    #[test]
+    fn histogram_percentiles() {
+        let histogram = BTreeMap::from([(1, 5), (3, 3), (10, 2)]);
+        assert_eq!(histogram_percentile(&histogram, 0.5), 1);
+        assert_eq!(histogram_percentile(&histogram, 0.6), 3);
+        assert_eq!(histogram_percentile(&histogram, 0.8), 3);
+        assert_eq!(histogram_percentile(&histogram, 0.9), 10);
+        assert_eq!(histogram_percentile(&histogram, 1.), 10);
+        assert_eq!(histogram_percentile(&BTreeMap::new(), 0.5), 0);
+    }
+
+    #[test]
+    fn dictionaries_of_different_lengths_are_benchmarked_together() {
+        let four = temp_path("four.txt");
+        let five = temp_path("five.txt");
+        std::fs::write(&four, "lane\nline\nlone\ncane\ncone\nbone\nbane\ntone\n").unwrap();
+        std::fs::write(&five, WORDS.join("\n")).unwrap();
+        let dictionaries: Vec<(String, Vec<&'static str>)> = [&four, &five]
+            .into_iter()
+            .map(|path| (path.clone(), load_dictionary(path).unwrap()))
+            .collect();
+
+        let capture = Capture::default();
+        let mut options = test_options(&capture, &["--sequential"]);
+        options.no_book = false;
+        benchmark_dictionaries(&dictionaries, &mut options);
+        let messages = capture.messages();
+        std::fs::remove_file(&four).unwrap();
+        std::fs::remove_file(&five).unwrap();
+
+        assert!(messages.contains(&format!("{}: 8 4-letter words", four)));
+        assert!(messages.contains(&format!("{}: 10 5-letter words", five)));
+        assert_eq!(
+            messages
+                .iter()
+                .filter(|m| *m == "Running Benchmark...")
+                .count(),
+            2
+        );
+        let totals: Vec<&String> = messages
+            .iter()
+            .filter(|m| m.contains(" letters: "))
+            .collect();
+        assert_eq!(totals.len(), 2);
+        assert!(totals[0].starts_with("4 letters: 100.0% accuracy"));
+        assert!(totals[0].ends_with("over 8 words"));
+        assert!(totals[1].starts_with("5 letters: 100.0% accuracy"));
+        assert!(totals[1].ends_with("over 10 words"));
 
-    fn get_word(&self, w: &str) -> usize {
-        w.chars().filter(|c| self.get(*c)).count()
+        // The solver options of the run are left as they were
+        assert_eq!(options.solver.first_guess, None);
+        assert!(!options.no_book);
     }
 }
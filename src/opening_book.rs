@@ -0,0 +1,113 @@
+use crate::{
+    hard_mode_legal, parse_pattern, pattern_string, possible_patterns, GuessCache, OpeningBook,
+    Solver, SolverOptions, WordLists, WordleAnswerColor,
+};
+use rayon::prelude::*;
+
+// Books shipped with the crate, built for the bundled word lists and default options
+const BOOK: &str = include_str!("book.txt");
+const HARD_BOOK: &str = include_str!("hard_book.txt");
+
+impl<const N: usize> OpeningBook<N> {
+    // Asks a fresh solver for its second guess after every pattern the opener can get against
+    // the solutions, so using the book never changes what the solver plays
+    pub fn build(word_lists: &WordLists, options: &SolverOptions) -> Self {
+        let solver = Solver::<N>::sized(word_lists, options.clone());
+        let opener = solver.opener().expect("a fresh solver has an opener");
+
+        let entries = possible_patterns(opener, &word_lists.solutions)
+            .into_par_iter()
+            .filter(|pattern| *pattern != [WordleAnswerColor::Green; N])
+            .map(|pattern| {
+                let mut solver = solver.clone();
+                solver.apply_feedback(opener, pattern);
+                (pattern, solver.best_guess())
+            })
+            .collect();
+
+        OpeningBook {
+            opener,
+            hard_mode: options.hard_mode,
+            entries,
+        }
+    }
+
+    // The bundled book for this mode, if it was built for the same word lists and options
+    pub fn builtin(word_lists: &WordLists, options: &SolverOptions) -> Option<Self> {
+        let text = if options.hard_mode { HARD_BOOK } else { BOOK };
+        let fingerprint = GuessCache::fingerprint(word_lists, options);
+
+        match Self::parse(text, &word_lists.guesses, options.hard_mode) {
+            Ok((book, built_for)) if built_for == fingerprint => Some(book),
+            Ok(_) => None,
+            Err(err) => {
+                log::warn!("ignoring the bundled opening book: {}", err);
+                None
+            }
+        }
+    }
+
+    // The first line is "# <opener> <fingerprint>" and every other line "<pattern> <guess>".
+    // Returns the book with the fingerprint it was built for
+    pub fn parse(
+        text: &str,
+        guesses: &[&'static str],
+        hard_mode: bool,
+    ) -> Result<(Self, u64), String> {
+        let word = |word: &str| {
+            guesses
+                .iter()
+                .copied()
+                .find(|&guess| guess == word)
+                .ok_or_else(|| format!("'{}' is not a valid guess", word))
+        };
+
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.next().unwrap_or_default();
+        let (opener, fingerprint) = match header.split_whitespace().collect::<Vec<_>>()[..] {
+            ["#", opener, fingerprint] => (
+                word(opener)?,
+                u64::from_str_radix(fingerprint, 16)
+                    .map_err(|_| format!("invalid fingerprint '{}'", fingerprint))?,
+            ),
+            _ => return Err(format!("invalid header '{}'", header)),
+        };
+
+        let mut book = OpeningBook {
+            opener,
+            hard_mode,
+            entries: Default::default(),
+        };
+
+        for line in lines {
+            let (pattern, guess) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [pattern, guess] => (
+                    parse_pattern(pattern)
+                        .ok_or_else(|| format!("invalid pattern '{}'", pattern))?,
+                    word(guess)?,
+                ),
+                _ => return Err(format!("invalid line '{}'", line)),
+            };
+
+            if hard_mode && !hard_mode_legal(guess, opener, &pattern) {
+                return Err(format!(
+                    "'{}' isn't allowed in hard mode after {} {}",
+                    guess,
+                    opener,
+                    pattern_string(&pattern)
+                ));
+            }
+            book.entries.insert(pattern, guess);
+        }
+
+        Ok((book, fingerprint))
+    }
+
+    pub fn to_text(&self, fingerprint: u64) -> String {
+        let mut text = format!("# {} {:016x}\n", self.opener, fingerprint);
+        for (pattern, guess) in &self.entries {
+            text.push_str(&format!("{} {}\n", pattern_string(pattern), guess));
+        }
+        text
+    }
+}
@@ -0,0 +1,119 @@
+// Letters are stored as bytes internally but serialized as chars so saved state stays readable
+use crate::{char_to_byte, Constraints, SeenLetterBitFlags, WordleAnswerColor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+// The per-position fields have one entry per letter of the word, checked when read back
+#[derive(Serialize, Deserialize)]
+pub struct ConstraintsRepr {
+    known_letters: Vec<Option<char>>,
+    included_letters: Vec<Vec<char>>,
+    excluded_letters: Vec<char>,
+    min_counts: BTreeMap<char, usize>,
+    max_counts: BTreeMap<char, usize>,
+}
+
+fn to_byte(c: char) -> Result<u8, String> {
+    char_to_byte(c).ok_or_else(|| format!("invalid letter '{}'", c))
+}
+
+fn to_bytes(letters: Vec<char>) -> Result<Vec<u8>, String> {
+    letters.into_iter().map(to_byte).collect()
+}
+
+fn to_byte_counts(counts: BTreeMap<char, usize>) -> Result<BTreeMap<u8, usize>, String> {
+    counts
+        .into_iter()
+        .map(|(c, count)| Ok((to_byte(c)?, count)))
+        .collect()
+}
+
+fn sized<T, const N: usize>(items: Vec<T>, field: &str) -> Result<[T; N], String> {
+    let len = items.len();
+    items
+        .try_into()
+        .map_err(|_| format!("{} has {} positions instead of {}", field, len, N))
+}
+
+impl<const N: usize> From<Constraints<N>> for ConstraintsRepr {
+    fn from(constraints: Constraints<N>) -> Self {
+        let to_chars = |letters: Vec<u8>| letters.into_iter().map(char::from).collect();
+        let to_char_counts = |counts: BTreeMap<u8, usize>| {
+            counts
+                .into_iter()
+                .map(|(c, count)| (char::from(c), count))
+                .collect()
+        };
+
+        ConstraintsRepr {
+            known_letters: constraints.known_letters.map(|c| c.map(char::from)).into(),
+            included_letters: constraints.included_letters.map(to_chars).into(),
+            excluded_letters: to_chars(constraints.excluded_letters),
+            min_counts: to_char_counts(constraints.min_counts),
+            max_counts: to_char_counts(constraints.max_counts),
+        }
+    }
+}
+
+impl<const N: usize> TryFrom<ConstraintsRepr> for Constraints<N> {
+    type Error = String;
+
+    fn try_from(repr: ConstraintsRepr) -> Result<Self, Self::Error> {
+        let known_letters = repr
+            .known_letters
+            .into_iter()
+            .map(|c| c.map(to_byte).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+        let included_letters = repr
+            .included_letters
+            .into_iter()
+            .map(to_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Constraints {
+            known_letters: sized(known_letters, "known_letters")?,
+            included_letters: sized(included_letters, "included_letters")?,
+            excluded_letters: to_bytes(repr.excluded_letters)?,
+            min_counts: to_byte_counts(repr.min_counts)?,
+            max_counts: to_byte_counts(repr.max_counts)?,
+        })
+    }
+}
+
+impl From<SeenLetterBitFlags> for String {
+    fn from(seen: SeenLetterBitFlags) -> Self {
+        ('a'..='z').filter(|&c| seen.get(c)).collect()
+    }
+}
+
+impl TryFrom<String> for SeenLetterBitFlags {
+    type Error = String;
+
+    fn try_from(letters: String) -> Result<Self, Self::Error> {
+        let mut seen = SeenLetterBitFlags::new();
+        for c in letters.chars() {
+            to_byte(c)?;
+            seen.set(c, true);
+        }
+        Ok(seen)
+    }
+}
+
+// serde only implements arrays up to a fixed size, so a turn's pattern goes through a Vec
+pub mod pattern {
+    use super::*;
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        pattern: &[WordleAnswerColor; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        pattern.as_slice().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[WordleAnswerColor; N], D::Error> {
+        let colors = Vec::<WordleAnswerColor>::deserialize(deserializer)?;
+        sized(colors, "pattern").map_err(serde::de::Error::custom)
+    }
+}
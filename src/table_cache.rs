@@ -0,0 +1,174 @@
+use crate::{
+    pattern_count, pattern_index, GuessCache, SolverOptions, WordLists, WordleAnswerColor,
+};
+use std::collections::HashMap;
+
+// Layout: magic, version, fingerprint (u64), entry count (u32), then for each entry the number
+// of turns (u8), each turn as a guess index (u16) and a base-3 pattern (u16), and finally the
+// index of the best guess (u16). Integers are little-endian and indices point into the guess
+// list the fingerprint was taken from. Patterns below 3^10 fit, so words up to 10 letters
+const MAGIC: &[u8; 4] = b"WBGC";
+const VERSION: u8 = 2;
+
+impl GuessCache {
+    // FNV-1a over both word lists and the solver options, since any of them can change which
+    // guess is best. Unlike DefaultHasher it's stable between builds
+    pub fn fingerprint(word_lists: &WordLists, options: &SolverOptions) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut feed = |bytes: &[u8]| {
+            for &b in bytes {
+                hash ^= b as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        };
+
+        for word in &word_lists.guesses {
+            feed(word.as_bytes());
+        }
+        feed(b"\0");
+        for word in &word_lists.solutions {
+            feed(word.as_bytes());
+        }
+        feed(b"\0");
+        feed(format!("{:?}", options).as_bytes());
+
+        hash
+    }
+}
+
+impl<const N: usize> GuessCache<N> {
+    // Entries mentioning a word outside `guesses` can't be indexed and are left out
+    pub fn encode(&self, guesses: &[&'static str], fingerprint: u64) -> Vec<u8> {
+        let index: HashMap<&str, u16> = guesses
+            .iter()
+            .enumerate()
+            .map(|(i, &word)| (word, i as u16))
+            .collect();
+
+        let entries = self.entries.lock().unwrap();
+        let mut encoded_entries = Vec::new();
+        let mut count: u32 = 0;
+
+        'entries: for (history, best) in entries.iter() {
+            let Some(&best) = index.get(best) else {
+                continue;
+            };
+
+            let mut entry = vec![history.len() as u8];
+            for (guess, pattern) in history {
+                let Some(&guess) = index.get(guess.as_str()) else {
+                    continue 'entries;
+                };
+                entry.extend_from_slice(&guess.to_le_bytes());
+                entry.extend_from_slice(&(pattern_index(pattern) as u16).to_le_bytes());
+            }
+            entry.extend_from_slice(&best.to_le_bytes());
+
+            encoded_entries.extend(entry);
+            count += 1;
+        }
+
+        let mut bytes = Vec::with_capacity(17 + encoded_entries.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&fingerprint.to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.extend(encoded_entries);
+        bytes
+    }
+
+    pub fn decode(
+        bytes: &[u8],
+        guesses: &[&'static str],
+        fingerprint: u64,
+    ) -> Result<GuessCache<N>, String> {
+        let mut reader = Reader { bytes, pos: 0 };
+
+        if reader.take(4)? != MAGIC {
+            return Err("not a table cache".to_owned());
+        }
+        if reader.u8()? != VERSION {
+            return Err("unsupported table cache version".to_owned());
+        }
+        if reader.u64()? != fingerprint {
+            return Err("built for different word lists or options".to_owned());
+        }
+
+        let word = |index: u16| {
+            guesses
+                .get(index as usize)
+                .copied()
+                .ok_or_else(|| format!("guess index {} out of range", index))
+        };
+
+        let cache = GuessCache::default();
+        let mut entries = cache.entries.lock().unwrap();
+        for _ in 0..reader.u32()? {
+            let turns = reader.u8()?;
+            let mut history = Vec::with_capacity(turns as usize);
+            for _ in 0..turns {
+                let guess = word(reader.u16()?)?;
+                let pattern = pattern_from_index(reader.u16()?)?;
+                history.push((guess.to_owned(), pattern));
+            }
+            entries.insert(history, word(reader.u16()?)?);
+        }
+
+        if reader.pos != bytes.len() {
+            return Err("trailing data".to_owned());
+        }
+
+        drop(entries);
+        Ok(cache)
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let slice = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| "unexpected end of table cache".to_owned())?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+fn pattern_from_index<const N: usize>(index: u16) -> Result<[WordleAnswerColor; N], String> {
+    let mut index = index as usize;
+    if index >= pattern_count(N) {
+        return Err(format!("invalid pattern {}", index));
+    }
+
+    let mut pattern = [WordleAnswerColor::Gray; N];
+    for color in pattern.iter_mut().rev() {
+        *color = match index % 3 {
+            0 => WordleAnswerColor::Green,
+            1 => WordleAnswerColor::Yellow,
+            _ => WordleAnswerColor::Gray,
+        };
+        index /= 3;
+    }
+    Ok(pattern)
+}
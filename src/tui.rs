@@ -0,0 +1,308 @@
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{self, Color},
+    terminal,
+};
+use ideal_wordle_player::*;
+use std::io::{self, Write};
+
+// Full-screen version of the assister. It takes the same 'guess result' input and redraws the
+// grid, the best guess and the letter bank after every key
+pub fn run_tui(
+    word_lists: &WordLists,
+    solver: Solver,
+    hard_mode: bool,
+    symbols: [char; 3],
+) -> io::Result<()> {
+    let mut state = TuiState::new(solver, hard_mode, symbols);
+    let mut out = io::stdout();
+
+    terminal::enable_raw_mode()?;
+    queue!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = (|| {
+        while !state.quit {
+            state.draw(&mut out)?;
+            out.flush()?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    state.handle_key(key, word_lists);
+                }
+            }
+        }
+        Ok(())
+    })();
+
+    // Restore the terminal even if drawing failed
+    queue!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    out.flush()?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+struct TuiState {
+    solver: Solver,
+    hard_mode: bool,
+    symbols: [char; 3],
+    best_guess: Option<&'static str>,
+    input: String,
+    message: String,
+    // Set once the game is over, after which any key quits
+    finished: bool,
+    quit: bool,
+}
+
+impl TuiState {
+    fn new(solver: Solver, hard_mode: bool, symbols: [char; 3]) -> TuiState {
+        let mut state = TuiState {
+            solver,
+            hard_mode,
+            symbols,
+            best_guess: None,
+            input: String::new(),
+            message: String::new(),
+            finished: false,
+            quit: false,
+        };
+        state.after_turn();
+        state
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, word_lists: &WordLists) {
+        if self.finished {
+            self.quit = true;
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.quit = true,
+            // Raw mode turns Ctrl-C into an ordinary key press instead of a signal
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Enter => self.submit(word_lists),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c)
+                if (c.is_ascii_alphabetic() || c == ' ' || self.symbols.contains(&c))
+                    && self.input.len() < 2 * WORD_LENGTH + 1 =>
+            {
+                self.input.push(if self.symbols.contains(&c) {
+                    c
+                } else {
+                    c.to_ascii_lowercase()
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn submit(&mut self, word_lists: &WordLists) {
+        let input = std::mem::take(&mut self.input);
+        let parts: Vec<&str> = input.split_whitespace().collect();
+
+        let [guess, result] = parts[..] else {
+            self.message = "Enter your guess and the result.".to_owned();
+            return;
+        };
+        let Some(output) = parse_pattern_with(result, self.symbols) else {
+            let [green, yellow, gray] = self.symbols;
+            self.message = format!(
+                "The result must be {} of '{}', '{}' or '{}'.",
+                WORD_LENGTH, green, yellow, gray
+            );
+            return;
+        };
+        let Some(&guess) = word_lists.guesses.iter().find(|&&word| word == guess) else {
+            self.message = format!("Guess '{}' is not a valid word.", guess);
+            return;
+        };
+        if let (true, Some(violation)) = (
+            self.hard_mode,
+            self.solver.game().hard_mode_violation(guess),
+        ) {
+            self.message = format!(
+                "Guess '{}' is not allowed in hard mode: {}.",
+                guess, violation
+            );
+            return;
+        }
+
+        self.solver.apply_feedback(guess, output);
+        self.message.clear();
+
+        if output == [WordleAnswerColor::Green; WORD_LENGTH] {
+            self.message = format!(
+                "Solved in {}/{}! Press any key to quit.",
+                self.solver.game().attempts(),
+                MAX_GUESSES
+            );
+            self.finished = true;
+        } else {
+            self.after_turn();
+        }
+    }
+
+    fn after_turn(&mut self) {
+        match self.solver.try_best_guess() {
+            Ok(best_guess) => self.best_guess = Some(best_guess),
+            Err(err) => {
+                self.message = format!("Can't suggest a guess: {}. Press any key to quit.", err);
+                self.best_guess = None;
+                self.finished = true;
+            }
+        }
+    }
+
+    // Best known color of each letter, None for letters that haven't been guessed
+    fn letter_bank(&self) -> [Option<WordleAnswerColor>; 26] {
+        let mut bank = [None; 26];
+        for turn in self.solver.game().turns() {
+            for (c, &color) in turn.guess.chars().zip(&turn.pattern) {
+                let Some(index) = letter_index(c) else {
+                    continue;
+                };
+                let known = &mut bank[index];
+                *known = Some(known.map_or(color, |known: WordleAnswerColor| known.min(color)));
+            }
+        }
+        bank
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, terminal::Clear(terminal::ClearType::All))?;
+        let mut row = 0;
+
+        line(
+            out,
+            &mut row,
+            "Enter your guess and the result, Esc or Ctrl-C to quit.",
+        )?;
+        line(out, &mut row, "")?;
+
+        let turns = self.solver.game().turns();
+        for i in 0..MAX_GUESSES {
+            line(out, &mut row, "  ")?;
+            match turns.get(i) {
+                Some(turn) => {
+                    for (c, &color) in turn.guess.chars().zip(&turn.pattern) {
+                        tile(out, &format!(" {} ", c.to_ascii_uppercase()), Some(color))?;
+                        queue!(out, style::Print(" "))?;
+                    }
+                }
+                None => queue!(out, style::Print(" _  ".repeat(WORD_LENGTH).trim_end()))?,
+            }
+        }
+        line(out, &mut row, "")?;
+
+        if let Some(best_guess) = self.best_guess {
+            line(out, &mut row, &format!("Best guess: {}", best_guess))?;
+        }
+        let remaining = format!("{} candidates remain", self.solver.candidates().len());
+        line(out, &mut row, &remaining)?;
+        line(out, &mut row, "")?;
+
+        line(out, &mut row, "  ")?;
+        for (c, color) in ('a'..='z').zip(self.letter_bank()) {
+            tile(out, &c.to_string(), color)?;
+            queue!(out, style::Print(" "))?;
+        }
+        line(out, &mut row, "")?;
+
+        line(out, &mut row, &format!("> {}", self.input))?;
+        line(out, &mut row, &self.message)
+    }
+}
+
+// Starts a new row; anything queued after it continues the same row
+fn line(out: &mut impl Write, row: &mut u16, text: &str) -> io::Result<()> {
+    queue!(out, cursor::MoveTo(0, *row), style::Print(text))?;
+    *row += 1;
+    Ok(())
+}
+
+fn tile(out: &mut impl Write, text: &str, color: Option<WordleAnswerColor>) -> io::Result<()> {
+    let background = match color {
+        Some(WordleAnswerColor::Green) => Color::DarkGreen,
+        Some(WordleAnswerColor::Yellow) => Color::DarkYellow,
+        Some(WordleAnswerColor::Gray) => Color::DarkGrey,
+        None => Color::Reset,
+    };
+
+    queue!(
+        out,
+        style::SetBackgroundColor(background),
+        style::Print(text),
+        style::ResetColor
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: [&str; 10] = [
+        "salet", "crane", "trace", "crate", "caret", "react", "slate", "least", "steal", "tales",
+    ];
+
+    fn new_state(word_lists: &WordLists) -> TuiState {
+        let solver = Solver::new(word_lists, SolverOptions::default());
+        TuiState::new(solver, false, ['g', 'y', 'x'])
+    }
+
+    // Types `text` followed by Enter, redrawing after every key like run_tui does
+    fn type_line(state: &mut TuiState, word_lists: &WordLists, text: &str) {
+        let keys = text.chars().map(KeyCode::Char).chain([KeyCode::Enter]);
+        for code in keys {
+            state.handle_key(KeyEvent::new(code, KeyModifiers::NONE), word_lists);
+            state.draw(&mut Vec::new()).unwrap();
+        }
+    }
+
+    #[test]
+    fn scripted_game_plays_through() {
+        let word_lists = WordLists {
+            guesses: WORDS.to_vec(),
+            solutions: WORDS.to_vec(),
+        };
+        let mut state = new_state(&word_lists);
+
+        type_line(&mut state, &word_lists, "zzzzz xxxxx");
+        assert_eq!(state.message, "Guess 'zzzzz' is not a valid word.");
+        type_line(&mut state, &word_lists, "salet xyxyy");
+        assert_eq!(state.solver.candidates(), ["trace", "crate"]);
+        type_line(&mut state, &word_lists, "crate ggggg");
+        assert!(state.finished && !state.quit);
+
+        state.handle_key(
+            KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE),
+            &word_lists,
+        );
+        assert!(state.quit);
+    }
+
+    #[test]
+    fn ctrl_c_quits() {
+        let word_lists = WordLists {
+            guesses: WORDS.to_vec(),
+            solutions: WORDS.to_vec(),
+        };
+        let mut state = new_state(&word_lists);
+
+        // A plain 'c' is still typed
+        state.handle_key(
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE),
+            &word_lists,
+        );
+        assert_eq!(state.input, "c");
+        assert!(!state.quit);
+
+        state.handle_key(
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            &word_lists,
+        );
+        assert!(state.quit);
+    }
+}
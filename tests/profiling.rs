@@ -0,0 +1,27 @@
+// The call counter is global to the process, so it's checked here in its own test binary
+// where nothing else is calling simulate_guess at the same time
+#![cfg(feature = "profiling")]
+
+use ideal_wordle_player::{feedback, guess_entropy, simulate_guess, simulate_guess_calls};
+
+#[test]
+fn simulate_guess_calls_are_counted() {
+    let words = ["salet", "crane", "trace", "crate", "caret"];
+    let before = simulate_guess_calls();
+
+    for guess in words {
+        for answer in words {
+            simulate_guess::<5>(answer, guess);
+        }
+    }
+    assert_eq!(simulate_guess_calls() - before, 25);
+
+    // Anything built on it counts too, once per word scored
+    guess_entropy("salet", &words);
+    assert_eq!(simulate_guess_calls() - before, 30);
+
+    // Input feedback rejects never reaches simulate_guess
+    feedback::<5>("crate", "sal").unwrap_err();
+    feedback::<5>("crate", "salet").unwrap();
+    assert_eq!(simulate_guess_calls() - before, 31);
+}